@@ -17,8 +17,39 @@ limitations under the License.
 // Bugzilla API documentation:
 // https://bugzilla.redhat.com/docs/en/html/api/core/v1/general.html
 
-use crate::bug_model::{Bug, Response};
+use std::collections::{HashMap, HashSet};
+use std::env::{self, VarError};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "cache")]
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+#[cfg(feature = "stream")]
+use futures_util::stream::Stream;
+use futures_util::stream::{self, StreamExt, TryStreamExt};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use crate::bug_model::{
+    Attachment, AttachmentsResponse, Bug, BugzillaError, Comment, CommentsResponse,
+    DependencyGraph, HistoryEntry, HistoryResponse, LoginResponse, RawResponse, Response,
+    SearchResult, SingleAttachmentResponse, SingleCommentResponse, User, UsersResponse,
+};
+#[cfg(feature = "cache")]
+use crate::cache::ResponseCache;
+use crate::change_model::{BugUpdate, CreatedResponse, NewBug, TagsResponse, UpdatedResponse};
 use crate::errors::BugzillaQueryError;
+use crate::instance_model::{FieldDefinition, FieldsResponse, Product, ProductsResponse};
+use crate::json_rpc;
+use crate::netrc;
+use crate::search::{encode, encode_params, encode_query, format_timestamp, SearchBuilder};
 
 /// Configuration and credentials to access a Bugzilla instance.
 pub struct BzInstance {
@@ -26,20 +57,190 @@ pub struct BzInstance {
     pub auth: Auth,
     pub pagination: Pagination,
     pub included_fields: Vec<String>,
+    /// The fields that Bugzilla removes from the response, even if they are included.
+    pub excluded_fields: Vec<String>,
+    /// The time limit for each request, or `None` for no limit.
+    pub timeout: Option<Duration>,
+    /// How many times to repeat a request after a transient failure.
+    pub max_retries: u32,
+    /// The value of the `User-Agent` header in each request.
+    pub user_agent: String,
+    /// The maximum number of bug IDs in a single request.
+    pub chunk_size: usize,
+    /// The maximum number of requests that run at the same time.
+    pub max_concurrency: usize,
+    /// The path to the REST API on the host, such as `/rest`.
+    pub rest_path: String,
+    /// The version of the REST API, such as `1` in `/rest/v1`, or `None` for the unversioned path.
+    pub api_version: Option<u32>,
+    /// The API that accesses bugs.
+    pub transport: Transport,
+    /// Additional headers in each request, as names and values.
+    pub headers: Vec<(String, String)>,
+    /// Whether accessing bugs by their IDs fails if Bugzilla doesn't return some of them.
+    pub strict: bool,
+    /// Whether a bug that fails to deserialize is skipped, rather than failing the whole response.
+    pub lenient: bool,
+    /// The product that searches use unless they set their own product.
+    pub default_product: Option<String>,
+    /// The component that searches use unless they set their own component.
+    pub default_component: Option<String>,
     client: reqwest::Client,
+    client_settings: ClientSettings,
+    last_retry_after: Mutex<Option<Duration>>,
+    #[cfg(feature = "cache")]
+    cache: Option<Arc<ResponseCache>>,
+}
+
+// The HTTP client shares its connection pool between the clones.
+impl Clone for BzInstance {
+    fn clone(&self) -> Self {
+        Self {
+            host: self.host.clone(),
+            auth: self.auth.clone(),
+            pagination: self.pagination.clone(),
+            included_fields: self.included_fields.clone(),
+            excluded_fields: self.excluded_fields.clone(),
+            timeout: self.timeout,
+            max_retries: self.max_retries,
+            user_agent: self.user_agent.clone(),
+            chunk_size: self.chunk_size,
+            max_concurrency: self.max_concurrency,
+            rest_path: self.rest_path.clone(),
+            api_version: self.api_version,
+            transport: self.transport,
+            headers: self.headers.clone(),
+            strict: self.strict,
+            lenient: self.lenient,
+            default_product: self.default_product.clone(),
+            default_component: self.default_component.clone(),
+            client: self.client.clone(),
+            client_settings: self.client_settings.clone(),
+            last_retry_after: Mutex::new(self.last_retry_after()),
+            #[cfg(feature = "cache")]
+            cache: self.cache.clone(),
+        }
+    }
+}
+
+/// Options that require building a new HTTP client when they change.
+#[derive(Clone, Default)]
+struct ClientSettings {
+    proxies: Vec<reqwest::Proxy>,
+    no_proxy: bool,
+    root_certificates: Vec<reqwest::Certificate>,
+    accept_invalid_certs: bool,
+    pool_max_idle_per_host: Option<usize>,
+    pool_idle_timeout: Option<Duration>,
+    http2_prior_knowledge: bool,
 }
 
 /// The authentication method that the crate uses when contacting Bugzilla.
+#[derive(Clone, Default)]
 pub enum Auth {
+    #[default]
     Anonymous,
     ApiKey(String),
     Basic { user: String, password: String },
+    /// A session token that you obtained by logging in. See `BzInstance::login`.
+    Token(String),
+}
+
+impl Auth {
+    /// Read the credentials from environment variables:
+    ///
+    /// * If `BUGZILLA_API_KEY` is set, use it as the API key.
+    /// * Otherwise, if both `BUGZILLA_USER` and `BUGZILLA_PASSWORD` are set, use them
+    ///   for basic authentication.
+    /// * Otherwise, access Bugzilla anonymously.
+    ///
+    /// # Errors
+    ///
+    /// Returns `BugzillaQueryError::MissingCredentials` if a variable is set,
+    /// but it's empty or not valid Unicode.
+    pub fn from_env() -> Result<Self, BugzillaQueryError> {
+        if let Some(key) = credential_from_env("BUGZILLA_API_KEY")? {
+            return Ok(Self::ApiKey(key));
+        }
+
+        let user = credential_from_env("BUGZILLA_USER")?;
+        let password = credential_from_env("BUGZILLA_PASSWORD")?;
+
+        match (user, password) {
+            (Some(user), Some(password)) => Ok(Self::Basic { user, password }),
+            _ => Ok(Self::Anonymous),
+        }
+    }
+
+    /// Read the credentials for the host from the `.netrc` file, for basic authentication.
+    ///
+    /// The file is at the path in the `NETRC` environment variable, or at `~/.netrc` by default.
+    /// The host is a name such as `bugzilla.redhat.com`, or a URL such as `https://bugzilla.redhat.com`.
+    /// If no entry matches the host, use the `default` entry. If there's no such entry
+    /// or the default file doesn't exist, access Bugzilla anonymously.
+    ///
+    /// # Errors
+    ///
+    /// Returns `BugzillaQueryError::Netrc` if the file can't be read, including
+    /// if the `NETRC` variable points to a file that doesn't exist.
+    pub fn from_netrc(host: &str) -> Result<Self, BugzillaQueryError> {
+        let (path, explicit) = match env::var_os("NETRC") {
+            Some(path) => (PathBuf::from(path), true),
+            None => match env::var_os("HOME") {
+                Some(home) => (Path::new(&home).join(".netrc"), false),
+                None => return Ok(Self::Anonymous),
+            },
+        };
+
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == io::ErrorKind::NotFound && !explicit => {
+                return Ok(Self::Anonymous);
+            }
+            Err(source) => return Err(BugzillaQueryError::Netrc { path, source }),
+        };
+
+        // Accept a URL as well as a bare host name.
+        let url = reqwest::Url::parse(host).ok();
+        let host = url
+            .as_ref()
+            .and_then(reqwest::Url::host_str)
+            .unwrap_or(host);
+
+        match netrc::credentials(&contents, host) {
+            Some((user, password)) => Ok(Self::Basic { user, password }),
+            None => Ok(Self::Anonymous),
+        }
+    }
+}
+
+// Print the kind of authentication, but never the secrets, so that logging is safe.
+impl fmt::Debug for Auth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const REDACTED: &str = "***";
+
+        match self {
+            Self::Anonymous => f.write_str("Anonymous"),
+            Self::ApiKey(_) => f.debug_tuple("ApiKey").field(&REDACTED).finish(),
+            Self::Basic { user, .. } => f
+                .debug_struct("Basic")
+                .field("user", user)
+                .field("password", &REDACTED)
+                .finish(),
+            Self::Token(_) => f.debug_tuple("Token").field(&REDACTED).finish(),
+        }
+    }
 }
 
-// We could set a default enum variant and derive, but that raises the MSRV to 1.62.
-impl Default for Auth {
-    fn default() -> Self {
-        Self::Anonymous
+/// Read the value of an environment variable that stores credentials.
+/// Returns `None` if the variable isn't set.
+fn credential_from_env(name: &str) -> Result<Option<String>, BugzillaQueryError> {
+    match env::var(name) {
+        Ok(value) if !value.is_empty() => Ok(Some(value)),
+        Ok(_) | Err(VarError::NotUnicode(_)) => {
+            Err(BugzillaQueryError::MissingCredentials(name.to_string()))
+        }
+        Err(VarError::NotPresent) => Ok(None),
     }
 }
 
@@ -48,64 +249,395 @@ impl Default for Auth {
 /// * `Default`: Use the default settings of this instance, which sets an arbitrary limit on the number of bugs.
 /// * `Limit`: Use this upper limit instead.
 /// * `Unlimited`: Set the limit to 0, which disables the upper limit and returns all matching bugs.
+/// * `Page`: Return at most `limit` bugs, skipping the first `offset` matching bugs.
+#[derive(Clone, Default)]
 pub enum Pagination {
+    #[default]
     Default,
     Limit(u32),
     Unlimited,
+    Page { limit: u32, offset: u32 },
 }
 
-// We could set a default enum variant and derive, but that raises the MSRV to 1.62.
-impl Default for Pagination {
-    fn default() -> Self {
-        Self::Default
+impl Pagination {
+    /// Returns the limit that this variant sends to Bugzilla, or `None` for the default limit
+    /// of the instance. `Unlimited` sends the limit of 0, which disables the limit.
+    #[must_use]
+    pub fn effective_limit(&self) -> Option<u32> {
+        match self {
+            Pagination::Default => None,
+            Pagination::Limit(n) | Pagination::Page { limit: n, .. } => Some(*n),
+            Pagination::Unlimited => Some(0),
+        }
     }
-}
 
-impl Pagination {
     /// Format the `Pagination` variant as a URL query fragment, such as `?limit=20`.
-    fn url_fragment(&self) -> String {
+    pub(crate) fn url_fragment(&self) -> String {
         match self {
             Pagination::Default => String::new(),
             Pagination::Limit(n) => format!("&limit={n}"),
             Pagination::Unlimited => "&limit=0".to_string(),
+            Pagination::Page { limit, offset } => format!("&limit={limit}&offset={offset}"),
+        }
+    }
+}
+
+/// Controls which API of Bugzilla the crate uses to access bugs:
+///
+/// * `Rest`: The REST API at `/rest`.
+/// * `JsonRpc`: The legacy JSON-RPC API at `/jsonrpc.cgi`, for older instances
+///   that don't provide the REST API. Only accessing bugs by their IDs or aliases
+///   and the searches, including counting bugs, use this API.
+///   Comments, attachments, history, and changes use the REST API regardless.
+///   The requests are never retried.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Transport {
+    #[default]
+    Rest,
+    JsonRpc,
+}
+
+/// Controls which dependencies of a bug a traversal follows:
+///
+/// * `DependsOn`: Follow the bugs that the bug depends on.
+/// * `Blocks`: Follow the bugs that the bug blocks.
+/// * `Both`: Follow the dependencies in both directions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    DependsOn,
+    Blocks,
+    Both,
+}
+
+impl Direction {
+    /// The IDs of the bugs that the bug links to in this direction.
+    fn neighbors(self, bug: &Bug) -> Vec<i32> {
+        match self {
+            Self::DependsOn => bug.depends_on.clone(),
+            Self::Blocks => bug.blocks.clone(),
+            Self::Both => bug.depends_on.iter().chain(&bug.blocks).copied().collect(),
         }
     }
 }
 
-/// The method of the request to Bugzilla. Either request specific IDs,
-/// or use a free-form Bugzilla search query as-is.
-enum Method<'a> {
+/// The method of the request to Bugzilla. Either request specific IDs or an alias,
+/// use a free-form Bugzilla search query, or use search parameters.
+/// The keys and values in queries and parameters are percent-encoded.
+pub(crate) enum Method<'a> {
     Ids(&'a [&'a str]),
+    Alias(&'a str),
     Search(&'a str),
+    Params(&'a [(String, String)]),
+    Quicksearch(&'a str),
+}
+
+/// The fields of the bugs that a request includes: either the fields configured
+/// in this `BzInstance`, or only the IDs of the bugs.
+#[derive(Clone, Copy)]
+enum Fields {
+    Configured,
+    Ids,
 }
 
-impl<'a> Method<'a> {
-    fn url_fragment(&self) -> String {
+impl Method<'_> {
+    pub(crate) fn url_fragment(&self) -> String {
+        match self {
+            Self::Ids(ids) => {
+                let ids: Vec<String> = ids.iter().map(|id| encode(id)).collect();
+                format!("id={}", ids.join(","))
+            }
+            Self::Alias(alias) => format!("alias={}", encode(alias)),
+            Self::Search(query) => encode_query(query),
+            Self::Params(params) => encode_params(params),
+            Self::Quicksearch(query) => format!("quicksearch={}", encode(query)),
+        }
+    }
+
+    /// The JSON-RPC method that accesses the bugs, such as `Bug.get`.
+    fn rpc_method(&self) -> &'static str {
+        match self {
+            Self::Ids(_) | Self::Alias(_) => "Bug.get",
+            Self::Search(_) | Self::Params(_) | Self::Quicksearch(_) => "Bug.search",
+        }
+    }
+
+    /// Check if the search sets the parameter, such as `product`.
+    /// Only the free-form and structured searches have parameters.
+    fn has_param(&self, key: &str) -> bool {
         match self {
-            Self::Ids(ids) => format!("id={}", ids.join(",")),
-            Self::Search(query) => (*query).to_string(),
+            Self::Search(query) => query
+                .split('&')
+                .any(|pair| pair.split('=').next() == Some(key)),
+            Self::Params(params) => params.iter().any(|(k, _)| k == key),
+            Self::Ids(_) | Self::Alias(_) | Self::Quicksearch(_) => false,
         }
     }
 }
 
+/// Deserialize the JSON body of a Bugzilla response. If the body is an error report
+/// from Bugzilla instead, return the report as an error.
+pub(crate) fn parse_response<T: DeserializeOwned>(body: &[u8]) -> Result<T, BugzillaQueryError> {
+    // Only try parsing the error report if the regular parsing fails,
+    // because error reports are rare and successful responses can be large.
+    serde_json::from_slice(body).map_err(|error| {
+        match serde_json::from_slice::<BugzillaError>(body) {
+            Ok(report) if report.error => BugzillaQueryError::Api {
+                code: report.code,
+                message: report.message,
+            },
+            _ => BugzillaQueryError::deserialization(error, body),
+        }
+    })
+}
+
+/// The Bugzilla error code that reports insufficient permissions to access a bug.
+const BUG_ACCESS_DENIED: i32 = 102;
+
+/// The error for a response with an unsuccessful HTTP status. Failed authentication
+/// and insufficient permissions have their own errors. Otherwise, if the body
+/// contains an error report from Bugzilla, return the report, or else return
+/// the status and the body.
+pub(crate) fn status_error(status: reqwest::StatusCode, body: String) -> BugzillaQueryError {
+    let report = serde_json::from_str::<BugzillaError>(&body)
+        .ok()
+        .filter(|report| report.error);
+
+    // Bugzilla reports a private bug as unauthorized, even though logging in
+    // might not help, so treat it as forbidden.
+    if report
+        .as_ref()
+        .map_or(false, |report| report.code == BUG_ACCESS_DENIED)
+    {
+        return BugzillaQueryError::Forbidden;
+    }
+
+    match (status, report) {
+        (reqwest::StatusCode::UNAUTHORIZED, _) => BugzillaQueryError::Unauthorized,
+        (reqwest::StatusCode::FORBIDDEN, _) => BugzillaQueryError::Forbidden,
+        (_, Some(report)) => BugzillaQueryError::Api {
+            code: report.code,
+            message: report.message,
+        },
+        (_, None) => BugzillaQueryError::HttpStatus {
+            status: status.as_u16(),
+            body,
+        },
+    }
+}
+
+/// Check the HTTP status of the response, and turn an unsuccessful status into an error.
+async fn check_status(
+    response: reqwest::Response,
+) -> Result<reqwest::Response, BugzillaQueryError> {
+    let status = response.status();
+    // A response that hasn't changed since the cached copy has no body to check.
+    #[cfg(feature = "cache")]
+    if status == reqwest::StatusCode::NOT_MODIFIED {
+        return Ok(response);
+    }
+    if status.is_success() {
+        Ok(response)
+    } else {
+        Err(status_error(status, response.text().await?))
+    }
+}
+
+/// Check that the host is an `http` or `https` URL, such as `https://bugzilla.redhat.com`,
+/// and remove the trailing slashes so that the host joins with paths correctly.
+pub(crate) fn normalize_host(host: String) -> Result<String, BugzillaQueryError> {
+    let is_valid = reqwest::Url::parse(&host).map_or(false, |url| {
+        matches!(url.scheme(), "http" | "https") && url.has_host()
+    });
+
+    if is_valid {
+        Ok(host.trim_end_matches('/').to_string())
+    } else {
+        Err(BugzillaQueryError::InvalidHost(host))
+    }
+}
+
+/// Report a single requested bug that doesn't exist as `NotFound`: either Bugzilla
+/// returned no bugs, the strict mode reported the bug as missing,
+/// or Bugzilla reported the error code 101 for a nonexistent bug.
+pub(crate) fn not_found_bug<T>(
+    id: &str,
+    result: Result<T, BugzillaQueryError>,
+) -> Result<T, BugzillaQueryError> {
+    match result {
+        Err(
+            BugzillaQueryError::NoBugs
+            | BugzillaQueryError::MissingBugs(_)
+            | BugzillaQueryError::Api { code: 101, .. },
+        ) => Err(BugzillaQueryError::NotFound { id: id.to_string() }),
+        result => result,
+    }
+}
+
+/// The requested IDs or aliases that match none of the returned bugs.
+fn missing_ids(ids: &[&str], bugs: &[Bug]) -> Vec<String> {
+    ids.iter()
+        .filter(|id| {
+            !bugs
+                .iter()
+                .any(|bug| bug.id.to_string() == **id || bug.alias.contains(id))
+        })
+        .map(|id| (*id).to_string())
+        .collect()
+}
+
+/// The offset of the page that follows the specified page of search results,
+/// or `None` if this is the last page.
+///
+/// The next page starts after the bugs that Bugzilla actually returned, including the bugs
+/// skipped in the lenient mode, because the server might cap the page below `page_size`.
+/// Without `total_matches`, only an empty page is the last one.
+fn next_page_offset(page: &SearchResult, offset: u32, page_size: u32) -> Option<u32> {
+    let returned = u32::try_from(page.bugs.len() + page.skipped).unwrap_or(u32::MAX);
+    if page_size == 0 || returned == 0 {
+        return None;
+    }
+
+    let next_offset = offset.saturating_add(returned);
+    match page.total_matches {
+        Some(total) if next_offset >= total => None,
+        _ => Some(next_offset),
+    }
+}
+
+/// The default `User-Agent` header, such as `bugzilla_query/1.1.0`.
+const DEFAULT_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+/// The default maximum number of bug IDs in a single request, which keeps the URL
+/// safely below the length limit of common servers.
+const DEFAULT_CHUNK_SIZE: usize = 200;
+
+/// The default path to the REST API on the Bugzilla host.
+const DEFAULT_REST_PATH: &str = "/rest";
+
+/// The default maximum number of requests that run at the same time.
+const DEFAULT_MAX_CONCURRENCY: usize = 4;
+
+/// The delay before repeating a request that failed in the specified attempt.
+/// The delay doubles with each attempt: 100 ms, 200 ms, 400 ms, and so on.
+fn retry_delay(attempt: u32) -> Duration {
+    let factor = 2_u64.saturating_pow(attempt.saturating_sub(1));
+    Duration::from_millis(100_u64.saturating_mul(factor))
+}
+
+/// Read the delay that the server requested in the `Retry-After` header,
+/// specified either in seconds or as an HTTP date.
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    let value = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim();
+
+    if let Ok(seconds) = value.parse() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    // The HTTP date format is compatible with RFC 2822.
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    // A date in the past means that the request can repeat right away.
+    let delay = (date.with_timezone(&Utc) - Utc::now())
+        .to_std()
+        .unwrap_or(Duration::ZERO);
+    Some(delay)
+}
+
 impl BzInstance {
     /// Create a new `BzInstance` struct using a host URL, with default values
     /// for all options.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidHost` if the host isn't a valid `http` or `https` URL.
     pub fn at(host: String) -> Result<Self, BugzillaQueryError> {
+        Self::with_client(host, reqwest::Client::new())
+    }
+
+    /// Create a new `BzInstance` struct using a host URL and an existing HTTP client,
+    /// with default values for all other options.
+    ///
+    /// Use this to share a client with custom settings, such as TLS roots or a connection pool,
+    /// across your application or across several `BzInstance`s.
+    /// The authentication and other options configured on `BzInstance` apply
+    /// on top of the settings of the client.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidHost` if the host isn't a valid `http` or `https` URL.
+    pub fn with_client(host: String, client: reqwest::Client) -> Result<Self, BugzillaQueryError> {
         // TODO: This function takes host as a String, even though client is happy with &str.
         // The String is only used in the host struct attribute.
 
-        let client = reqwest::Client::new();
-
         Ok(BzInstance {
-            host,
+            host: normalize_host(host)?,
             client,
             included_fields: vec!["_default".to_string()],
+            excluded_fields: Vec::new(),
             auth: Auth::default(),
             pagination: Pagination::default(),
+            timeout: None,
+            max_retries: 0,
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            max_concurrency: DEFAULT_MAX_CONCURRENCY,
+            rest_path: DEFAULT_REST_PATH.to_string(),
+            api_version: None,
+            strict: false,
+            lenient: false,
+            default_product: None,
+            default_component: None,
+            headers: Vec::new(),
+            transport: Transport::default(),
+            client_settings: ClientSettings::default(),
+            last_retry_after: Mutex::new(None),
+            #[cfg(feature = "cache")]
+            cache: None,
         })
     }
 
+    /// Build a new HTTP client with the current client settings, replacing the previous client,
+    /// including a client passed to `with_client`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the TLS backend cannot be initialized, just like `reqwest::Client::new`.
+    fn rebuild_client(&mut self) {
+        let settings = &self.client_settings;
+        let mut builder = reqwest::Client::builder();
+
+        for proxy in &settings.proxies {
+            builder = builder.proxy(proxy.clone());
+        }
+        // Disabling proxies also clears the proxies added above.
+        if settings.no_proxy {
+            builder = builder.no_proxy();
+        }
+        for certificate in &settings.root_certificates {
+            builder = builder.add_root_certificate(certificate.clone());
+        }
+        builder = builder.danger_accept_invalid_certs(settings.accept_invalid_certs);
+        if let Some(max_idle) = settings.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max_idle);
+        }
+        if let Some(timeout) = settings.pool_idle_timeout {
+            builder = builder.pool_idle_timeout(timeout);
+        }
+        if settings.http2_prior_knowledge {
+            builder = builder.http2_prior_knowledge();
+        }
+
+        #[allow(clippy::expect_used)]
+        let client = builder
+            .build()
+            .expect("Failed to initialize the HTTP client.");
+        self.client = client;
+    }
+
     /// Set the authentication method of this `BzInstance`.
     #[must_use]
     pub fn authenticate(mut self, auth: Auth) -> Self {
@@ -131,98 +663,1764 @@ impl BzInstance {
         self
     }
 
-    /// Format the included Bugzilla fields as a URL query fragment, such as `&include_fields=_default,flags`.
+    /// Request all fields of the bugs, including the custom fields, such as `cf_doc_type`.
+    /// This sets the included fields to `_all`.
+    ///
+    /// Requesting all fields makes the responses considerably larger and slower,
+    /// especially in searches that return many bugs. If you only need a few
+    /// custom fields, add them with `include_extra_fields` instead.
     #[must_use]
-    fn fields_as_query(&self) -> String {
-        if self.included_fields.is_empty() {
-            String::new()
-        } else {
-            format!("&include_fields={}", self.included_fields.join(","))
+    pub fn include_all_fields(mut self) -> Self {
+        self.included_fields = vec!["_all".to_string()];
+        self
+    }
+
+    /// Add Bugzilla fields that this `BzInstance` will request, in addition
+    /// to the fields that it already requests, such as `_default`.
+    ///
+    /// Fields that the list already contains are not added again.
+    #[must_use]
+    pub fn include_extra_fields(mut self, fields: Vec<String>) -> Self {
+        for field in fields {
+            if !self.included_fields.contains(&field) {
+                self.included_fields.push(field);
+            }
         }
+        self
     }
 
-    /// Based on the request method, form a complete, absolute URL
-    /// to download the tickets from the REST API.
+    /// Request the `flags` field in addition to the current fields,
+    /// so that the flag methods of `Bug`, such as `get_flag`, can find the flags.
     #[must_use]
-    fn path(&self, method: &Method) -> String {
-        format!(
-            "{}/rest/bug?{}{}{}",
-            &self.host,
-            method.url_fragment(),
-            self.fields_as_query(),
-            self.pagination.url_fragment()
-        )
+    pub fn with_flags(self) -> Self {
+        self.include_extra_fields(vec!["flags".to_string()])
     }
 
-    /// Download the specified URL using the configured authentication.
-    async fn authenticated_get(&self, url: &str) -> Result<reqwest::Response, reqwest::Error> {
-        let request_builder = self.client.get(url);
-        let authenticated = match &self.auth {
-            Auth::Anonymous => request_builder,
-            Auth::ApiKey(key) => request_builder.header("Authorization", &format!("Bearer {key}")),
-            Auth::Basic { user, password } => request_builder.basic_auth(user, Some(password)),
-        };
-        authenticated.send().await
+    /// Request the `tags` field in addition to the current fields.
+    /// The tags are personal, so they require authentication.
+    #[must_use]
+    pub fn with_tags(self) -> Self {
+        self.include_extra_fields(vec!["tags".to_string()])
     }
 
-    /// Access several bugs by their IDs.
-    pub async fn bugs(&self, ids: &[&str]) -> Result<Vec<Bug>, BugzillaQueryError> {
-        // If the user specifies no IDs, skip network requests and return no bugs.
-        // Returning an error could also be valid, but I believe that this behavior
-        // is less surprising and more practical.
-        if ids.is_empty() {
-            return Ok(Vec::new());
-        }
+    /// Set Bugzilla fields that this `BzInstance` will leave out of the responses,
+    /// such as the large `cc_detail` field.
+    ///
+    /// Bugzilla applies the excluded fields after the included fields, so a field
+    /// that is both included and excluded is left out. For example, include `_all`
+    /// and exclude a few fields to request all fields except for those.
+    #[must_use]
+    pub fn exclude_fields(mut self, fields: Vec<String>) -> Self {
+        self.excluded_fields = fields;
+        self
+    }
 
-        let url = self.path(&Method::Ids(ids));
+    /// Set the time limit for each request that this `BzInstance` sends.
+    ///
+    /// The limit covers the whole request, from connecting until the response body
+    /// finishes downloading. A request that exceeds the limit fails with
+    /// `BugzillaQueryError::Timeout`. By default, requests have no time limit.
+    #[must_use]
+    pub fn timeout(mut self, duration: Duration) -> Self {
+        self.timeout = Some(duration);
+        self
+    }
 
-        // Gets a bug by ID and deserializes the JSON to data variable
-        let response = self
-            .authenticated_get(&url)
-            .await?
-            .json::<Response>()
-            .await?;
+    /// Set how many times this `BzInstance` repeats a request after a transient failure,
+    /// such as a server error or a dropped connection.
+    ///
+    /// The delay between attempts grows exponentially, starting at 100 milliseconds.
+    /// If the server rate-limits the requests or requests a delay in the `Retry-After` header,
+    /// the next attempt waits for that delay instead.
+    /// Client errors, such as 404 Not Found, are not transient and fail immediately.
+    /// If all attempts fail, the request fails with `BugzillaQueryError::RetriesExhausted`.
+    /// By default, requests are not repeated.
+    #[must_use]
+    pub fn retry(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
 
-        log::debug!("{:#?}", response);
+    /// Send all requests from this `BzInstance` through the specified proxy.
+    ///
+    /// To use an authenticated proxy, set the credentials with `reqwest::Proxy::basic_auth`.
+    /// To exclude certain hosts from proxying, use `reqwest::Proxy::no_proxy`.
+    /// You can call this method repeatedly to add several proxies, such as separate ones
+    /// for HTTP and HTTPS.
+    ///
+    /// This method replaces the HTTP client, including a client passed to `with_client`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the TLS backend cannot be initialized, just like `reqwest::Client::new`.
+    #[must_use]
+    pub fn proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.client_settings.proxies.push(proxy);
+        self.rebuild_client();
+        self
+    }
 
-        // The resulting list might be empty. In that case, return an error.
-        if response.bugs.is_empty() {
-            Err(BugzillaQueryError::NoBugs)
-        } else {
-            Ok(response.bugs)
-        }
+    /// Disable all proxies for this `BzInstance`, including the proxies set with the `proxy`
+    /// method and the system proxies configured in environment variables such as `HTTPS_PROXY`.
+    ///
+    /// This method replaces the HTTP client, including a client passed to `with_client`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the TLS backend cannot be initialized, just like `reqwest::Client::new`.
+    #[must_use]
+    pub fn no_proxy(mut self) -> Self {
+        self.client_settings.no_proxy = true;
+        self.rebuild_client();
+        self
     }
 
-    /// Access a single bug by its ID.
-    pub async fn bug(&self, id: &str) -> Result<Bug, BugzillaQueryError> {
-        // Reuse the `bugs` function. Later, extract the first element.
-        let bugs = self.bugs(&[id]).await?;
+    /// Set the maximum number of idle connections to each host that this `BzInstance` keeps
+    /// open for reuse. By default, the number isn't limited, like in `reqwest`.
+    ///
+    /// This method replaces the HTTP client, including a client passed to `with_client`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the TLS backend cannot be initialized, just like `reqwest::Client::new`.
+    #[must_use]
+    pub fn pool_max_idle_per_host(mut self, max_idle: usize) -> Self {
+        self.client_settings.pool_max_idle_per_host = Some(max_idle);
+        self.rebuild_client();
+        self
+    }
 
-        // This is a way to return the first (and only) element of the Vec,
-        // without cloning it.
-        bugs.into_iter().next().ok_or(BugzillaQueryError::NoBugs)
+    /// Set how long an idle connection stays open for reuse, such as between repeated polls.
+    /// By default, idle connections close after 90 seconds, like in `reqwest`.
+    ///
+    /// This method replaces the HTTP client, including a client passed to `with_client`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the TLS backend cannot be initialized, just like `reqwest::Client::new`.
+    #[must_use]
+    pub fn pool_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.client_settings.pool_idle_timeout = Some(timeout);
+        self.rebuild_client();
+        self
     }
 
-    /// Access bugs using a free-form Bugzilla search query.
+    /// Send all requests over HTTP/2 without negotiating the protocol first,
+    /// for servers that you know support HTTP/2. Servers that don't support it fail the requests.
     ///
-    /// An example of a query: `component=rust&product=Fedora&version=36`.
-    pub async fn search(&self, query: &str) -> Result<Vec<Bug>, BugzillaQueryError> {
-        let url = self.path(&Method::Search(query));
+    /// By default, the protocol is negotiated with each server.
+    ///
+    /// This method replaces the HTTP client, including a client passed to `with_client`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the TLS backend cannot be initialized, just like `reqwest::Client::new`.
+    #[must_use]
+    pub fn http2_prior_knowledge(mut self, enable: bool) -> Self {
+        self.client_settings.http2_prior_knowledge = enable;
+        self.rebuild_client();
+        self
+    }
 
-        // Gets the bugs by query and deserializes the JSON to data variable
-        let response = self
-            .authenticated_get(&url)
-            .await?
-            .json::<Response>()
-            .await?;
+    /// Trust the specified root certificate, in addition to the system trust store,
+    /// such as the certificate that signed the certificate of an internal Bugzilla instance.
+    /// You can call this method repeatedly to add several certificates.
+    ///
+    /// This method replaces the HTTP client, including a client passed to `with_client`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the TLS backend cannot be initialized, just like `reqwest::Client::new`.
+    #[must_use]
+    pub fn add_root_certificate(mut self, certificate: reqwest::Certificate) -> Self {
+        self.client_settings.root_certificates.push(certificate);
+        self.rebuild_client();
+        self
+    }
+
+    /// Accept any TLS certificate from Bugzilla, including invalid, expired,
+    /// and self-signed certificates.
+    ///
+    /// **Warning:** This makes the connection vulnerable to man-in-the-middle attacks,
+    /// which can expose your credentials. Prefer trusting the certificate of the instance
+    /// with `add_root_certificate`.
+    ///
+    /// This method replaces the HTTP client, including a client passed to `with_client`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the TLS backend cannot be initialized, just like `reqwest::Client::new`.
+    #[must_use]
+    pub fn danger_accept_invalid_certs(mut self, accept: bool) -> Self {
+        self.client_settings.accept_invalid_certs = accept;
+        self.rebuild_client();
+        self
+    }
 
-        log::debug!("{:#?}", response);
+    /// Whether this `BzInstance` accepts invalid TLS certificates.
+    /// See `danger_accept_invalid_certs`.
+    #[must_use]
+    pub fn accepts_invalid_certs(&self) -> bool {
+        self.client_settings.accept_invalid_certs
+    }
 
-        // The resulting list might be empty. In that case, return an error.
-        if response.bugs.is_empty() {
-            Err(BugzillaQueryError::NoBugs)
-        } else {
-            Ok(response.bugs)
-        }
+    /// Set the `User-Agent` header that this `BzInstance` sends with each request,
+    /// so that Bugzilla administrators can identify your application.
+    ///
+    /// By default, the header identifies this crate and its version, such as `bugzilla_query/1.1.0`.
+    #[must_use]
+    pub fn user_agent(mut self, user_agent: String) -> Self {
+        self.user_agent = user_agent;
+        self
+    }
+
+    /// Set the path to the REST API on the host, if it differs from `/rest`,
+    /// such as `/bugzilla/rest` behind a reverse proxy.
+    ///
+    /// The leading and trailing slashes are optional.
+    #[must_use]
+    pub fn rest_path(mut self, path: String) -> Self {
+        self.rest_path = path;
+        self
+    }
+
+    /// Set the version of the REST API, which adds a path segment such as `/rest/v1`.
+    /// Some instances only accept the versioned path.
+    ///
+    /// By default, the version is `None`, and the path is unversioned, such as `/rest/bug`.
+    #[must_use]
+    pub fn api_version(mut self, version: Option<u32>) -> Self {
+        self.api_version = version;
+        self
+    }
+
+    /// Add a header that this `BzInstance` sends with each request, such as a header
+    /// that an authentication proxy requires. Call this method repeatedly to add
+    /// several headers.
+    ///
+    /// The headers add to the authentication headers rather than replacing them.
+    #[must_use]
+    pub fn header(mut self, name: String, value: String) -> Self {
+        self.headers.push((name, value));
+        self
+    }
+
+    /// Set the API that this `BzInstance` uses to access bugs by their IDs or aliases
+    /// and to search.
+    ///
+    /// By default, `BzInstance` uses the REST API.
+    #[must_use]
+    pub fn transport(mut self, transport: Transport) -> Self {
+        self.transport = transport;
+        self
+    }
+
+    /// Enable or disable the strict mode. In the strict mode, accessing bugs by their IDs
+    /// fails with `MissingBugs` if Bugzilla doesn't return some of the bugs,
+    /// such as because they don't exist or they're private.
+    ///
+    /// By default, the strict mode is disabled, and the missing bugs are left out.
+    #[must_use]
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Limit the searches from this `BzInstance` to the product,
+    /// such as `Fedora`, so that the queries don't have to repeat it.
+    ///
+    /// A search that sets its own `product` parameter uses that instead,
+    /// rather than combining both products. The scope applies to free-form
+    /// and structured searches, but not to quicksearches, saved searches,
+    /// or accessing bugs by their IDs. A saved search keeps the scope that it was saved with.
+    #[must_use]
+    pub fn default_product(mut self, product: String) -> Self {
+        self.default_product = Some(product);
+        self
+    }
+
+    /// Limit the searches from this `BzInstance` to the component, such as `rust`.
+    ///
+    /// A search that sets its own `component` parameter uses that instead.
+    /// See `default_product` for which searches use the scope.
+    #[must_use]
+    pub fn default_component(mut self, component: String) -> Self {
+        self.default_component = Some(component);
+        self
+    }
+
+    /// Enable or disable the lenient mode. In the lenient mode, each bug in a response
+    /// deserializes separately, and the bugs that fail, such as because of a malformed field,
+    /// are logged and skipped. The `skipped` field of `SearchResult` counts them.
+    ///
+    /// By default, the lenient mode is disabled, and a single malformed bug fails the whole response.
+    #[must_use]
+    pub fn lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+
+    /// Cache the responses from Bugzilla in memory. Bugzilla identifies each response
+    /// with an `ETag` header, and if a repeated request gets the same response,
+    /// Bugzilla skips sending it and this `BzInstance` uses the cached copy instead.
+    ///
+    /// The clones of this `BzInstance` share the cache.
+    #[cfg(feature = "cache")]
+    #[must_use]
+    pub fn with_cache(mut self) -> Self {
+        self.cache = Some(Arc::new(ResponseCache::default()));
+        self
+    }
+
+    /// Set the maximum number of bug IDs that this `BzInstance` requests at once.
+    ///
+    /// When you request more bugs by ID, the request splits into several requests,
+    /// so that the URL doesn't exceed the length limit of the server. The default is 200.
+    #[must_use]
+    pub fn chunk_size(mut self, size: usize) -> Self {
+        self.chunk_size = size;
+        self
+    }
+
+    /// Set the maximum number of requests that this `BzInstance` sends at the same time,
+    /// such as when a long list of IDs splits into several requests. The default is 4.
+    #[must_use]
+    pub fn max_concurrency(mut self, max: usize) -> Self {
+        self.max_concurrency = max;
+        self
+    }
+
+    /// Returns the delay that the server most recently requested in the `Retry-After` header,
+    /// such as when it rate-limits the requests. Returns None if it has never requested a delay.
+    #[must_use]
+    pub fn last_retry_after(&self) -> Option<Duration> {
+        self.last_retry_after.lock().ok().and_then(|delay| *delay)
+    }
+
+    /// Format the included and excluded Bugzilla fields as a URL query fragment,
+    /// such as `&include_fields=_default,flags&exclude_fields=cc_detail`.
+    #[must_use]
+    fn fields_as_query(&self) -> String {
+        let mut fragment = String::new();
+        if !self.included_fields.is_empty() {
+            fragment.push_str("&include_fields=");
+            fragment.push_str(&self.included_fields.join(","));
+        }
+        if !self.excluded_fields.is_empty() {
+            fragment.push_str("&exclude_fields=");
+            fragment.push_str(&self.excluded_fields.join(","));
+        }
+        fragment
+    }
+
+    /// Form a complete, absolute URL to the specified REST API endpoint,
+    /// such as `bug` or `bug/1906883/comment`.
+    #[must_use]
+    fn rest_url(&self, endpoint: &str) -> String {
+        // Join the parts with single slashes, regardless of the slashes that they contain.
+        let host = self.host.trim_end_matches('/');
+        let mut url = host.to_string();
+        let rest_path = self.rest_path.trim_matches('/');
+        if !rest_path.is_empty() {
+            url.push('/');
+            url.push_str(rest_path);
+        }
+        if let Some(version) = self.api_version {
+            url.push_str("/v");
+            url.push_str(&version.to_string());
+        }
+        url.push('/');
+        url.push_str(endpoint);
+        url
+    }
+
+    /// Based on the request method, form a complete, absolute URL
+    /// to download the tickets from the REST API.
+    #[must_use]
+    fn path(&self, method: &Method) -> String {
+        self.paginated_path(method, &self.pagination)
+    }
+
+    /// Form the URL like `path`, but with the specified pagination
+    /// instead of the pagination configured in this `BzInstance`.
+    #[must_use]
+    fn paginated_path(&self, method: &Method, pagination: &Pagination) -> String {
+        self.fields_path(method, pagination, Fields::Configured)
+    }
+
+    /// Form the URL like `paginated_path`, but with the specified fields.
+    #[must_use]
+    fn fields_path(&self, method: &Method, pagination: &Pagination, fields: Fields) -> String {
+        let scope = self.scope_params(method);
+        let scope = if scope.is_empty() {
+            String::new()
+        } else {
+            format!("&{}", encode_params(&scope))
+        };
+
+        format!(
+            "{}?{}{}{}{}",
+            self.rest_url("bug"),
+            method.url_fragment(),
+            scope,
+            match fields {
+                Fields::Configured => self.fields_as_query(),
+                Fields::Ids => "&include_fields=id".to_string(),
+            },
+            pagination.url_fragment()
+        )
+    }
+
+    /// The default product and component that the search doesn't set itself.
+    /// A saved search already defines its scope, so it gets no default scope.
+    fn scope_params(&self, method: &Method) -> Vec<(String, String)> {
+        if !matches!(method, Method::Search(_) | Method::Params(_))
+            || method.has_param("savedsearch")
+        {
+            return Vec::new();
+        }
+
+        [
+            ("product", &self.default_product),
+            ("component", &self.default_component),
+        ]
+        .into_iter()
+        .filter(|(key, _)| !method.has_param(key))
+        .filter_map(|(key, value)| value.as_ref().map(|value| (key.to_string(), value.clone())))
+        .collect()
+    }
+
+    /// Download the specified URL using the configured authentication.
+    /// If `conditional` is true, ask Bugzilla to skip the response if it hasn't changed
+    /// since the cached copy.
+    #[cfg_attr(not(feature = "cache"), allow(unused_variables))]
+    async fn send_get(
+        &self,
+        url: &str,
+        conditional: bool,
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        #[cfg_attr(not(feature = "cache"), allow(unused_mut))]
+        let mut request_builder = self.request(reqwest::Method::GET, url);
+
+        #[cfg(feature = "cache")]
+        if conditional {
+            if let Some(etag) = self.cache.as_ref().and_then(|cache| cache.etag(url)) {
+                request_builder = request_builder.header(reqwest::header::IF_NONE_MATCH, etag);
+            }
+        }
+
+        request_builder.send().await
+    }
+
+    /// Show the URL of the request with the token in the query redacted,
+    /// so that it's safe to log. Other credentials don't appear in the URL.
+    #[must_use]
+    fn redacted_url(&self, url: String) -> String {
+        match self.auth {
+            Auth::Token(_) => format!("{url}&token=REDACTED"),
+            _ => url,
+        }
+    }
+
+    /// Show the request that the request method would send: either the REST URL,
+    /// or the JSON-RPC URL and body, with the credentials redacted.
+    fn debug_request(&self, method: &Method) -> String {
+        match self.transport {
+            Transport::Rest => self.redacted_url(self.path(method)),
+            Transport::JsonRpc => {
+                let mut params = self.rpc_params(method, &self.pagination, Fields::Configured);
+                for key in ["Bugzilla_api_key", "Bugzilla_password", "Bugzilla_token"] {
+                    if let Some(value) = params.get_mut(key) {
+                        *value = json!("REDACTED");
+                    }
+                }
+                let body = json_rpc::request_body(method.rpc_method(), params);
+                format!("POST {} {body}", self.rpc_url())
+            }
+        }
+    }
+
+    /// Show the URL that a search with the free-form query would request,
+    /// without sending the request. Use this to diagnose misbehaving queries.
+    ///
+    /// With the JSON-RPC transport, this shows the URL and the body of the `POST` request.
+    /// The credentials in the URL or the body are redacted.
+    #[must_use]
+    pub fn debug_url_for_search(&self, query: &str) -> String {
+        self.debug_request(&Method::Search(query))
+    }
+
+    /// Show the URL that accessing the bugs by their IDs would request,
+    /// without sending the request. Use this to diagnose misbehaving queries.
+    ///
+    /// If the list contains more IDs than the chunk size, this is the URL of the first chunk.
+    /// With the JSON-RPC transport, this shows the URL and the body of the `POST` request.
+    /// The credentials in the URL or the body are redacted.
+    #[must_use]
+    pub fn debug_url_for_ids(&self, ids: &[&str]) -> String {
+        let chunk = ids
+            .chunks(self.chunk_size.max(1))
+            .next()
+            .unwrap_or_default();
+        self.debug_request(&Method::Ids(chunk))
+    }
+
+    /// The URL of the JSON-RPC API on the Bugzilla host.
+    fn rpc_url(&self) -> String {
+        format!("{}/jsonrpc.cgi", self.host.trim_end_matches('/'))
+    }
+
+    /// Prepare a request to the specified URL with the configured authentication
+    /// and the other per-request options.
+    fn request(&self, method: reqwest::Method, url: &str) -> reqwest::RequestBuilder {
+        let mut request_builder = self
+            .client
+            .request(method, url)
+            .header(reqwest::header::USER_AGENT, &self.user_agent);
+        for (name, value) in &self.headers {
+            request_builder = request_builder.header(name, value);
+        }
+        if let Some(timeout) = self.timeout {
+            request_builder = request_builder.timeout(timeout);
+        }
+        match &self.auth {
+            Auth::Anonymous => request_builder,
+            // The authentication headers are marked as sensitive, so that they never appear in logs.
+            Auth::ApiKey(key) => request_builder.bearer_auth(key),
+            Auth::Basic { user, password } => request_builder.basic_auth(user, Some(password)),
+            Auth::Token(token) => request_builder.query(&[("token", token)]),
+        }
+    }
+
+    /// Download the specified URL using the configured authentication, conditionally
+    /// on the cached copy like `send_get`.
+    /// If retries are enabled, repeat the request after transient failures.
+    async fn authenticated_get(
+        &self,
+        url: &str,
+        conditional: bool,
+    ) -> Result<reqwest::Response, BugzillaQueryError> {
+        let mut attempts = 0;
+
+        loop {
+            attempts += 1;
+            let result = self.send_get(url, conditional).await;
+
+            let retry_after = result.as_ref().ok().and_then(retry_after);
+            if retry_after.is_some() {
+                if let Ok(mut last_retry_after) = self.last_retry_after.lock() {
+                    *last_retry_after = retry_after;
+                }
+            }
+
+            // Server errors, rate limiting, and dropped connections might disappear on their own.
+            // Timeouts aren't worth repeating because each attempt would take just as long.
+            let is_transient = match &result {
+                Ok(response) => {
+                    response.status().is_server_error()
+                        || response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS
+                }
+                Err(error) => error.is_connect() || (error.is_request() && !error.is_timeout()),
+            };
+
+            if !is_transient || self.max_retries == 0 {
+                return check_status(result?).await;
+            }
+            if attempts > self.max_retries {
+                return Err(BugzillaQueryError::RetriesExhausted { attempts });
+            }
+
+            // If the server requested a delay, respect it instead of the exponential backoff.
+            let delay = retry_after.unwrap_or_else(|| retry_delay(attempts));
+            log::debug!("Transient failure on attempt {attempts}. Retrying in {delay:?}.");
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// Form the JSON-RPC parameters for the request method, including the authentication,
+    /// the fields, and the pagination.
+    fn rpc_params(
+        &self,
+        method: &Method,
+        pagination: &Pagination,
+        fields: Fields,
+    ) -> Map<String, Value> {
+        let mut params = Map::new();
+
+        match method {
+            Method::Ids(ids) => {
+                params.insert("ids".to_string(), json!(ids));
+            }
+            Method::Alias(alias) => {
+                params.insert("ids".to_string(), json!([alias]));
+            }
+            Method::Search(query) => json_rpc::query_params(query, &mut params),
+            Method::Params(pairs) => {
+                for (key, value) in *pairs {
+                    json_rpc::add_param(&mut params, key, json!(value));
+                }
+            }
+            Method::Quicksearch(query) => {
+                params.insert("quicksearch".to_string(), json!(query));
+            }
+        }
+        for (key, value) in self.scope_params(method) {
+            params.insert(key, json!(value));
+        }
+
+        match fields {
+            Fields::Configured => {
+                if !self.included_fields.is_empty() {
+                    params.insert("include_fields".to_string(), json!(self.included_fields));
+                }
+                if !self.excluded_fields.is_empty() {
+                    params.insert("exclude_fields".to_string(), json!(self.excluded_fields));
+                }
+            }
+            Fields::Ids => {
+                params.insert("include_fields".to_string(), json!(["id"]));
+            }
+        }
+
+        match *pagination {
+            Pagination::Default => {}
+            Pagination::Limit(limit) => {
+                params.insert("limit".to_string(), json!(limit));
+            }
+            Pagination::Unlimited => {
+                params.insert("limit".to_string(), json!(0));
+            }
+            Pagination::Page { limit, offset } => {
+                params.insert("limit".to_string(), json!(limit));
+                params.insert("offset".to_string(), json!(offset));
+            }
+        }
+
+        match &self.auth {
+            Auth::Anonymous => {}
+            Auth::ApiKey(key) => {
+                params.insert("Bugzilla_api_key".to_string(), json!(key));
+            }
+            Auth::Basic { user, password } => {
+                params.insert("Bugzilla_login".to_string(), json!(user));
+                params.insert("Bugzilla_password".to_string(), json!(password));
+            }
+            Auth::Token(token) => {
+                params.insert("Bugzilla_token".to_string(), json!(token));
+            }
+        }
+
+        params
+    }
+
+    /// Access the bugs that the request method specifies, using the configured transport,
+    /// and deserialize the response.
+    async fn get_response<T: DeserializeOwned>(
+        &self,
+        method: &Method<'_>,
+    ) -> Result<T, BugzillaQueryError> {
+        self.get_response_with(method, &self.pagination, Fields::Configured)
+            .await
+    }
+
+    /// Access the bugs like `get_response`, but with the specified pagination and fields
+    /// instead of the configuration of this `BzInstance`.
+    async fn get_response_with<T: DeserializeOwned>(
+        &self,
+        method: &Method<'_>,
+        pagination: &Pagination,
+        fields: Fields,
+    ) -> Result<T, BugzillaQueryError> {
+        match self.transport {
+            Transport::Rest => {
+                self.get_json(&self.fields_path(method, pagination, fields))
+                    .await
+            }
+            Transport::JsonRpc => {
+                let url = self.rpc_url();
+                let params = self.rpc_params(method, pagination, fields);
+                let body = json_rpc::request_body(method.rpc_method(), params);
+
+                let response = self
+                    .request(reqwest::Method::POST, &url)
+                    .json(&body)
+                    .send()
+                    .await?;
+                let body = check_status(response).await?.bytes().await?;
+                json_rpc::parse_envelope(&body)
+            }
+        }
+    }
+
+    /// Access the bugs that the request method specifies, using the configured transport.
+    /// In the lenient mode, skip the bugs that fail to deserialize.
+    async fn get_bugs(&self, method: &Method<'_>) -> Result<Response, BugzillaQueryError> {
+        self.get_bugs_with(method, &self.pagination, Fields::Configured)
+            .await
+    }
+
+    /// Access the bugs like `get_bugs`, but with the specified pagination and fields
+    /// instead of the configuration of this `BzInstance`.
+    async fn get_bugs_with(
+        &self,
+        method: &Method<'_>,
+        pagination: &Pagination,
+        fields: Fields,
+    ) -> Result<Response, BugzillaQueryError> {
+        if self.lenient {
+            let response: RawResponse = self.get_response_with(method, pagination, fields).await?;
+            Ok(response.into_lenient())
+        } else {
+            self.get_response_with(method, pagination, fields).await
+        }
+    }
+
+    /// Download the specified URL using the configured authentication
+    /// and deserialize the JSON response.
+    async fn get_json<T: DeserializeOwned>(&self, url: &str) -> Result<T, BugzillaQueryError> {
+        #[cfg(feature = "cache")]
+        if let Some(cache) = &self.cache {
+            let mut response = self.authenticated_get(url, true).await?;
+            if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+                if let Some(body) = cache.body(url) {
+                    log::debug!("Using the cached response to {url}.");
+                    return parse_response(&body);
+                }
+                // The cached copy disappeared since the request, so request the full response.
+                response = self.authenticated_get(url, false).await?;
+                if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+                    return Err(status_error(response.status(), String::new()));
+                }
+            }
+            let etag = response
+                .headers()
+                .get(reqwest::header::ETAG)
+                .and_then(|etag| etag.to_str().ok())
+                .map(ToString::to_string);
+            let body = response.bytes().await?;
+            if let Some(etag) = etag {
+                cache.store(url, etag, body.to_vec());
+            }
+            return parse_response(&body);
+        }
+
+        self.get_json_uncached(url).await
+    }
+
+    /// Download the specified URL like `get_json`, but without the cache.
+    /// Use this for the URLs that contain credentials, which must not become cache keys.
+    async fn get_json_uncached<T: DeserializeOwned>(
+        &self,
+        url: &str,
+    ) -> Result<T, BugzillaQueryError> {
+        let response = self.authenticated_get(url, false).await?;
+
+        // Only a conditional request can result in an unchanged response.
+        #[cfg(feature = "cache")]
+        if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+            return Err(status_error(response.status(), String::new()));
+        }
+
+        let body = response.bytes().await?;
+        parse_response(&body)
+    }
+
+    /// Send the JSON body to the specified URL in a request that changes data,
+    /// such as `POST` or `PUT`, using the configured authentication,
+    /// and deserialize the JSON response.
+    ///
+    /// Changes require credentials, so fail early without sending the request
+    /// if the authentication is anonymous. Changes are never retried.
+    async fn send_json<B: Serialize, T: DeserializeOwned>(
+        &self,
+        method: reqwest::Method,
+        url: &str,
+        body: &B,
+    ) -> Result<T, BugzillaQueryError> {
+        if let Auth::Anonymous = self.auth {
+            return Err(BugzillaQueryError::Unauthorized);
+        }
+
+        let response = self.request(method, url).json(body).send().await?;
+        let body = check_status(response).await?.bytes().await?;
+        parse_response(&body)
+    }
+
+    /// Log into Bugzilla with a user name and a password. On success, this `BzInstance`
+    /// authenticates all subsequent requests with the session token that Bugzilla returned,
+    /// as if you set `Auth::Token`.
+    ///
+    /// Use this method on instances that don't accept API keys.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails, or if Bugzilla rejects the credentials.
+    pub async fn login(&mut self, user: &str, password: &str) -> Result<(), BugzillaQueryError> {
+        let url = reqwest::Url::parse_with_params(
+            &self.rest_url("login"),
+            &[("login", user), ("password", password)],
+        )
+        .map_err(|_| BugzillaQueryError::InvalidUrl(self.host.clone()))?;
+
+        // Don't log the response, because it contains the token. The URL contains
+        // the password, so don't cache the response either.
+        let response: LoginResponse = self.get_json_uncached(url.as_str()).await?;
+        log::debug!("Logged into Bugzilla as user {}.", response.id);
+
+        self.auth = Auth::Token(response.token);
+        Ok(())
+    }
+
+    /// Access several bugs by their IDs, and return the bugs that Bugzilla returned,
+    /// which can be none.
+    ///
+    /// If the list contains more IDs than the configured chunk size, the IDs are split
+    /// into several requests, which run concurrently up to the configured limit.
+    /// The bugs are returned in the order of the chunks.
+    async fn fetch_bugs(&self, ids: &[&str]) -> Result<Vec<Bug>, BugzillaQueryError> {
+        // Request the bugs in chunks so that the URL doesn't exceed the length limit.
+        // An invalid chunk size of 0 would panic, so treat it as 1.
+        // Collect the requests first, so that the future of this function stays `Send`
+        // and users can spawn it as a task.
+        let requests: Vec<_> = ids
+            .chunks(self.chunk_size.max(1))
+            .enumerate()
+            .map(|(index, chunk)| async move {
+                // Gets a bug by ID and deserializes the JSON to data variable
+                let response = self.get_bugs(&Method::Ids(chunk)).await?;
+
+                log::debug!("{response:#?}");
+
+                Ok::<_, BugzillaQueryError>((index, response.bugs))
+            })
+            .collect();
+
+        // Send several requests at once, and fail on the first error.
+        let mut chunks: Vec<(usize, Vec<Bug>)> = stream::iter(requests)
+            .buffer_unordered(self.max_concurrency.max(1))
+            .try_collect()
+            .await?;
+
+        // The requests finish in any order, so restore the original order.
+        chunks.sort_by_key(|(index, _)| *index);
+        Ok(chunks.into_iter().flat_map(|(_, bugs)| bugs).collect())
+    }
+
+    /// Access several bugs by their IDs.
+    ///
+    /// If the list contains more IDs than the configured chunk size, the IDs are split
+    /// into several requests, which run concurrently up to the configured limit.
+    /// The bugs are returned in the order of the chunks.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails, or if Bugzilla returns no bugs
+    /// for a non-empty list of IDs. In the strict mode, returns `MissingBugs`
+    /// if Bugzilla doesn't return some of the bugs.
+    pub async fn bugs(&self, ids: &[&str]) -> Result<Vec<Bug>, BugzillaQueryError> {
+        // If the user specifies no IDs, skip network requests and return no bugs.
+        // Returning an error could also be valid, but I believe that this behavior
+        // is less surprising and more practical.
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let bugs = self.fetch_bugs(ids).await?;
+
+        if self.strict {
+            let missing = missing_ids(ids, &bugs);
+            if !missing.is_empty() {
+                return Err(BugzillaQueryError::MissingBugs(missing));
+            }
+        }
+
+        // The resulting list might be empty. In that case, return an error.
+        if bugs.is_empty() {
+            Err(BugzillaQueryError::NoBugs)
+        } else {
+            Ok(bugs)
+        }
+    }
+
+    /// Access several bugs by their IDs, and key the bugs by their IDs.
+    ///
+    /// The list can also contain aliases, which match the aliases of the returned bugs.
+    /// If you configure the included fields, include `alias` in that case.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MissingBugs` with the requested IDs that Bugzilla didn't return,
+    /// or another error if the request fails.
+    pub async fn bugs_map(&self, ids: &[&str]) -> Result<HashMap<i32, Bug>, BugzillaQueryError> {
+        let bugs = self.fetch_bugs(ids).await?;
+
+        let missing = missing_ids(ids, &bugs);
+        if missing.is_empty() {
+            Ok(bugs.into_iter().map(|bug| (bug.id, bug)).collect())
+        } else {
+            Err(BugzillaQueryError::MissingBugs(missing))
+        }
+    }
+
+    /// Access a single bug by its ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotFound` if the bug does not exist, or another error if the request fails.
+    pub async fn bug(&self, id: &str) -> Result<Bug, BugzillaQueryError> {
+        // Reuse the `bugs` function. Later, extract the first element.
+        let bugs = not_found_bug(id, self.bugs(&[id]).await)?;
+
+        // This is a way to return the first (and only) element of the Vec,
+        // without cloning it.
+        bugs.into_iter()
+            .next()
+            .ok_or_else(|| BugzillaQueryError::NotFound { id: id.to_string() })
+    }
+
+    /// Access the bug and all bugs that it transitively depends on or blocks,
+    /// up to `max_depth` steps away from the bug.
+    ///
+    /// The traversal requests each level of the dependency tree together.
+    /// It visits each bug only once, so cycles in the dependencies are safe.
+    /// Bugs that are missing from the responses, such as private bugs, are skipped.
+    /// If you configure the included fields, include `depends_on` and `blocks`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a request fails, or if the bug does not exist.
+    pub async fn dependency_tree(
+        &self,
+        id: &str,
+        direction: Direction,
+        max_depth: usize,
+    ) -> Result<DependencyGraph, BugzillaQueryError> {
+        let root = self.bug(id).await?;
+        let root_id = root.id;
+
+        let mut visited: HashSet<i32> = HashSet::from([root_id]);
+        let mut frontier = vec![root];
+        let mut bugs = HashMap::new();
+
+        for _ in 0..max_depth {
+            let next_ids: Vec<String> = frontier
+                .iter()
+                .flat_map(|bug| direction.neighbors(bug))
+                .filter(|id| visited.insert(*id))
+                .map(|id| id.to_string())
+                .collect();
+
+            bugs.extend(frontier.drain(..).map(|bug| (bug.id, bug)));
+
+            if next_ids.is_empty() {
+                break;
+            }
+
+            let next_ids: Vec<&str> = next_ids.iter().map(String::as_str).collect();
+            frontier = self.fetch_bugs(&next_ids).await?;
+        }
+
+        bugs.extend(frontier.into_iter().map(|bug| (bug.id, bug)));
+
+        // Only list the neighbors that the traversal reached.
+        let neighbors = bugs
+            .values()
+            .map(|bug| {
+                let reached = direction
+                    .neighbors(bug)
+                    .into_iter()
+                    .filter(|id| bugs.contains_key(id))
+                    .collect();
+                (bug.id, reached)
+            })
+            .collect();
+
+        Ok(DependencyGraph {
+            root: root_id,
+            bugs,
+            neighbors,
+        })
+    }
+
+    /// Access the bug that the specified bug duplicates, if it's marked as a duplicate.
+    ///
+    /// This follows a single step: if the target bug is also a duplicate,
+    /// call this method again with the target bug.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails, or if the target bug does not exist.
+    pub async fn duplicate_of(&self, bug: &Bug) -> Result<Option<Bug>, BugzillaQueryError> {
+        match bug.dupe_of {
+            Some(id) => self.bug(&id.to_string()).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Access the bugs that block the specified bug, which are listed in its `depends_on` field.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails.
+    pub async fn blockers(&self, bug: &Bug) -> Result<Vec<Bug>, BugzillaQueryError> {
+        self.bugs_by_numeric_ids(&bug.depends_on).await
+    }
+
+    /// Access the bugs that the specified bug blocks, which are listed in its `blocks` field.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails.
+    pub async fn blocked_by(&self, bug: &Bug) -> Result<Vec<Bug>, BugzillaQueryError> {
+        self.bugs_by_numeric_ids(&bug.blocks).await
+    }
+
+    /// Access several bugs by their numeric IDs. An empty list returns no bugs.
+    async fn bugs_by_numeric_ids(&self, ids: &[i32]) -> Result<Vec<Bug>, BugzillaQueryError> {
+        let ids: Vec<String> = ids.iter().map(ToString::to_string).collect();
+        let ids: Vec<&str> = ids.iter().map(String::as_str).collect();
+        self.bugs(&ids).await
+    }
+
+    /// Access a single bug by its ID as the raw JSON object, without deserializing it
+    /// into `Bug`. Use this if the bug model can't represent a field on your instance.
+    ///
+    /// The request uses the configured authentication, pagination, and fields.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotFound` if the bug does not exist, or another error if the request fails.
+    pub async fn bug_raw(&self, id: &str) -> Result<Value, BugzillaQueryError> {
+        let response: Result<RawResponse, _> = self.get_response(&Method::Ids(&[id])).await;
+        let bug = response.and_then(|response| {
+            response
+                .bugs
+                .into_iter()
+                .next()
+                .ok_or(BugzillaQueryError::NoBugs)
+        });
+
+        not_found_bug(id, bug)
+    }
+
+    /// Access a single bug by its alias, such as `stylo`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotFound` if no bug has the alias, or another error if the request fails.
+    pub async fn bug_by_alias(&self, alias: &str) -> Result<Bug, BugzillaQueryError> {
+        let bug = self
+            .get_bugs(&Method::Alias(alias))
+            .await
+            .and_then(|response| {
+                log::debug!("{response:#?}");
+
+                response
+                    .bugs
+                    .into_iter()
+                    .next()
+                    .ok_or(BugzillaQueryError::NoBugs)
+            });
+
+        not_found_bug(alias, bug)
+    }
+
+    /// Access bugs using a free-form Bugzilla search query.
+    ///
+    /// An example of a query: `component=rust&product=Fedora&version=36`.
+    ///
+    /// The values in the query are percent-encoded, so they can contain spaces
+    /// and reserved characters, such as in `summary=foo bar&component=C/C%2B%2B`.
+    /// Values that are already percent-encoded keep their meaning, and `+` stands
+    /// for a space, like in the queries that the Bugzilla web UI shows.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails, or if no bugs match the query.
+    pub async fn search(&self, query: &str) -> Result<Vec<Bug>, BugzillaQueryError> {
+        // Gets the bugs by query and deserializes the JSON to data variable
+        let response = SearchResult::from(self.get_bugs(&Method::Search(query)).await?);
+
+        log::debug!("{response:#?}");
+
+        if response.is_truncated() {
+            log::warn!(
+                "The search returned {} of {} matching bugs. Configure the pagination to get more.",
+                response.bugs.len(),
+                response.total_matches.unwrap_or_default()
+            );
+        }
+
+        // The resulting list might be empty. In that case, return an error.
+        if response.bugs.is_empty() {
+            Err(BugzillaQueryError::NoBugs)
+        } else {
+            Ok(response.bugs)
+        }
+    }
+
+    /// Access bugs using several free-form Bugzilla search queries, such as saved searches.
+    ///
+    /// The searches run concurrently up to the configured limit. The results are returned
+    /// in the order of the queries. A query that matches no bugs results in an empty list
+    /// rather than an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error if any of the requests fails.
+    pub async fn search_many(&self, queries: &[&str]) -> Result<Vec<Vec<Bug>>, BugzillaQueryError> {
+        let requests: Vec<_> = queries
+            .iter()
+            .map(|query| async move {
+                let response = self.get_bugs(&Method::Search(query)).await?;
+
+                log::debug!("{response:#?}");
+
+                Ok::<_, BugzillaQueryError>(response.bugs)
+            })
+            .collect();
+
+        stream::iter(requests)
+            .buffered(self.max_concurrency.max(1))
+            .try_collect()
+            .await
+    }
+
+    /// Access bugs using a free-form Bugzilla search query, along with
+    /// the pagination metadata that Bugzilla reported for the search.
+    ///
+    /// Compare the number of returned bugs with `total_matches` to detect
+    /// whether the configured pagination truncated the results.
+    /// No matching bugs result in an empty list rather than an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails.
+    pub async fn search_full(&self, query: &str) -> Result<SearchResult, BugzillaQueryError> {
+        let response = self.get_bugs(&Method::Search(query)).await?;
+
+        log::debug!("{response:#?}");
+
+        Ok(SearchResult::from(response))
+    }
+
+    /// Access bugs using the Bugzilla quicksearch syntax, which is the same
+    /// as in the search box in the Bugzilla web UI.
+    ///
+    /// An example of a quicksearch: `ALL product:Fedora component:rust`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails, or if no bugs match the quicksearch.
+    pub async fn quicksearch(&self, query: &str) -> Result<Vec<Bug>, BugzillaQueryError> {
+        let response = self.get_bugs(&Method::Quicksearch(query)).await?;
+
+        log::debug!("{response:#?}");
+
+        // The resulting list might be empty. In that case, return an error.
+        if response.bugs.is_empty() {
+            Err(BugzillaQueryError::NoBugs)
+        } else {
+            Ok(response.bugs)
+        }
+    }
+
+    /// Access bugs using a search that you constructed with `SearchBuilder`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails, or if no bugs match the search.
+    pub async fn search_with(&self, search: SearchBuilder) -> Result<Vec<Bug>, BugzillaQueryError> {
+        let params = search.to_params();
+        let response = self.get_bugs(&Method::Params(&params)).await?;
+
+        log::debug!("{response:#?}");
+
+        // The resulting list might be empty. In that case, return an error.
+        if response.bugs.is_empty() {
+            Err(BugzillaQueryError::NoBugs)
+        } else {
+            Ok(response.bugs)
+        }
+    }
+
+    /// Count the bugs that match a free-form Bugzilla search query,
+    /// without downloading the bugs.
+    ///
+    /// If no bugs match the query, the count is 0 rather than an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails.
+    pub async fn count(&self, query: &str) -> Result<u32, BugzillaQueryError> {
+        let method = Method::Search(query);
+
+        // Request the ID of a single bug. Bugzilla reports the number of all matching bugs
+        // along with it.
+        let response = self
+            .get_bugs_with(&method, &Pagination::Limit(1), Fields::Ids)
+            .await?;
+
+        if let Some(total_matches) = response.total_matches {
+            return Ok(total_matches);
+        }
+
+        // Older Bugzilla versions don't report the number of matches,
+        // so count the IDs of all matching bugs instead.
+        let response = self
+            .get_bugs_with(&method, &Pagination::Unlimited, Fields::Ids)
+            .await?;
+
+        Ok(u32::try_from(response.bugs.len()).unwrap_or(u32::MAX))
+    }
+
+    /// Access bugs using a free-form Bugzilla search query, limited to bugs
+    /// that changed at the specified time or later.
+    ///
+    /// Use this for incremental synchronization. Because no bugs changing since
+    /// the last synchronization is a common result, no matching bugs result
+    /// in an empty list rather than an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails.
+    pub async fn search_changed_since(
+        &self,
+        query: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<Bug>, BugzillaQueryError> {
+        let since = format_timestamp(since);
+        let query = if query.is_empty() {
+            format!("last_change_time={since}")
+        } else {
+            format!("{query}&last_change_time={since}")
+        };
+        let response = self.get_bugs(&Method::Search(&query)).await?;
+
+        log::debug!("{response:#?}");
+
+        Ok(response.bugs)
+    }
+
+    /// Access bugs with the keywords. If `match_all` is true, the bugs must have
+    /// all of the keywords. Otherwise, any of the keywords is enough.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails, or if no bugs have the keywords.
+    pub async fn search_by_keywords(
+        &self,
+        keywords: &[&str],
+        match_all: bool,
+    ) -> Result<Vec<Bug>, BugzillaQueryError> {
+        self.search_with(SearchBuilder::new().keywords(keywords, match_all))
+            .await
+    }
+
+    /// Access bugs assigned to the user, identified by their email.
+    /// No matching bugs result in an empty list rather than an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails.
+    pub async fn search_by_assignee(&self, email: &str) -> Result<Vec<Bug>, BugzillaQueryError> {
+        match self
+            .search_with(SearchBuilder::new().assigned_to(email))
+            .await
+        {
+            Err(BugzillaQueryError::NoBugs) => Ok(Vec::new()),
+            result => result,
+        }
+    }
+
+    /// Access bugs reported by the user, identified by their email.
+    /// No matching bugs result in an empty list rather than an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails.
+    pub async fn search_by_reporter(&self, email: &str) -> Result<Vec<Bug>, BugzillaQueryError> {
+        match self.search_with(SearchBuilder::new().reporter(email)).await {
+            Err(BugzillaQueryError::NoBugs) => Ok(Vec::new()),
+            result => result,
+        }
+    }
+
+    /// Access bugs using a saved search, identified by its name.
+    /// To run a search that another user shared, specify the ID of that user as `sharer_id`.
+    ///
+    /// Saved searches usually require authentication. The default product and component
+    /// of this `BzInstance` don't apply, because the saved search defines its own scope.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails, or if the search matches no bugs.
+    pub async fn saved_search(
+        &self,
+        name: &str,
+        sharer_id: Option<&str>,
+    ) -> Result<Vec<Bug>, BugzillaQueryError> {
+        self.search_with(SearchBuilder::saved_search(name, sharer_id))
+            .await
+    }
+
+    /// Access bugs that could duplicate a bug with the specified summary,
+    /// because their summary contains some of the same words.
+    /// Optionally, only search in a single product.
+    ///
+    /// See `SearchBuilder::possible_duplicates` for the search that this method uses.
+    /// No matching bugs result in an empty list rather than an error.
+    /// If the summary has no words to search for, the method sends no request
+    /// and returns an empty list.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails.
+    pub async fn possible_duplicates(
+        &self,
+        summary: &str,
+        product: Option<&str>,
+    ) -> Result<Vec<Bug>, BugzillaQueryError> {
+        let search = match SearchBuilder::possible_duplicates(summary, product) {
+            Some(search) => search,
+            None => return Ok(Vec::new()),
+        };
+
+        match self.search_with(search).await {
+            Err(BugzillaQueryError::NoBugs) => Ok(Vec::new()),
+            result => result,
+        }
+    }
+
+    /// Access a single page of bugs using a free-form Bugzilla search query.
+    ///
+    /// The page contains at most `limit` bugs, starting after the first `offset` matching bugs.
+    /// This overrides the pagination configured in this `BzInstance`.
+    /// Use the `total_matches` field of the result to detect the last page.
+    /// A page past the last matching bug contains no bugs, rather than resulting in an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails.
+    pub async fn search_paginated(
+        &self,
+        query: &str,
+        limit: u32,
+        offset: u32,
+    ) -> Result<SearchResult, BugzillaQueryError> {
+        let pagination = Pagination::Page { limit, offset };
+        let response = self
+            .get_bugs_with(&Method::Search(query), &pagination, Fields::Configured)
+            .await?;
+
+        log::debug!("{response:#?}");
+
+        Ok(SearchResult::from(response))
+    }
+
+    /// Access bugs using a free-form Bugzilla search query, returning at most `limit` bugs.
+    ///
+    /// The limit only applies to this request. It overrides the pagination configured
+    /// in this `BzInstance` without changing it. A `limit` of 0 disables the limit.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails, or if the search matches no bugs.
+    pub async fn search_limited(
+        &self,
+        query: &str,
+        limit: u32,
+    ) -> Result<Vec<Bug>, BugzillaQueryError> {
+        let pagination = Pagination::Limit(limit);
+        let response = self
+            .get_bugs_with(&Method::Search(query), &pagination, Fields::Configured)
+            .await?;
+
+        log::debug!("{response:#?}");
+
+        // The resulting list might be empty. In that case, return an error.
+        if response.bugs.is_empty() {
+            Err(BugzillaQueryError::NoBugs)
+        } else {
+            Ok(response.bugs)
+        }
+    }
+
+    /// Access bugs using a free-form Bugzilla search query as a stream,
+    /// which downloads the matching bugs lazily, one page at a time.
+    ///
+    /// Each page contains at most `page_size` bugs. The stream requests the next page
+    /// only after the consumer has taken all bugs from the previous page,
+    /// and it ends after reaching `total_matches` or after an empty page.
+    /// A `page_size` of 0 disables the limit, so the stream makes a single request.
+    ///
+    /// If a request fails, the stream yields the error and ends.
+    #[cfg(feature = "stream")]
+    pub fn search_stream<'a>(
+        &'a self,
+        query: &'a str,
+        page_size: u32,
+    ) -> impl Stream<Item = Result<Bug, BugzillaQueryError>> + 'a {
+        // The state of the stream is the offset of the next page, or `None` after the last page.
+        stream::unfold(Some(0), move |offset| async move {
+            let offset = offset?;
+
+            match self.search_paginated(query, page_size, offset).await {
+                Ok(page) => {
+                    let next_state = next_page_offset(&page, offset, page_size);
+                    let bugs: Vec<_> = page.bugs.into_iter().map(Ok).collect();
+
+                    Some((stream::iter(bugs), next_state))
+                }
+                Err(error) => Some((stream::iter(vec![Err(error)]), None)),
+            }
+        })
+        .flatten()
+    }
+
+    /// Access all bugs that match a free-form Bugzilla search query,
+    /// downloading them in pages of at most `page_size` bugs until reaching `total_matches`
+    /// or an empty page. Each page starts after the bugs that the previous page returned.
+    ///
+    /// Unlike `Pagination::Unlimited`, this works even if the instance caps the number
+    /// of bugs in a single response. This overrides the pagination configured in this `BzInstance`.
+    /// A `page_size` of 0 disables the limit, so the method makes a single request.
+    ///
+    /// If a page contains the same bugs as the previous page, such as because the instance
+    /// ignores the offset, the method stops and returns the bugs without the repeated page.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the requests fails, or if the search matches no bugs.
+    pub async fn search_all(
+        &self,
+        query: &str,
+        page_size: u32,
+    ) -> Result<Vec<Bug>, BugzillaQueryError> {
+        let mut bugs = Vec::new();
+        let mut offset = 0;
+        let mut previous_ids = Vec::new();
+
+        loop {
+            let page = self.search_paginated(query, page_size, offset).await?;
+            let next_offset = next_page_offset(&page, offset, page_size);
+
+            // Without `total_matches`, an instance that ignores the offset would otherwise
+            // return the same page forever.
+            let ids: Vec<i32> = page.bugs.iter().map(|bug| bug.id).collect();
+            if !ids.is_empty() && ids == previous_ids {
+                log::warn!("The search returned the same page twice. Stopping at offset {offset}.");
+                break;
+            }
+
+            bugs.extend(page.bugs);
+            previous_ids = ids;
+
+            match next_offset {
+                Some(next_offset) => offset = next_offset,
+                None => break,
+            }
+        }
+
+        if bugs.is_empty() {
+            Err(BugzillaQueryError::NoBugs)
+        } else {
+            Ok(bugs)
+        }
+    }
+
+    /// Access all comments in a bug, including the bug description,
+    /// which is the comment with `count` set to 0.
+    ///
+    /// Private comments are only visible to the members of the insider group.
+    /// With anonymous access, Bugzilla doesn't return private comments at all.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails, or if Bugzilla returns
+    /// no comments for the bug.
+    pub async fn comments(&self, id: &str) -> Result<Vec<Comment>, BugzillaQueryError> {
+        let url = self.rest_url(&format!("bug/{id}/comment"));
+
+        let response: CommentsResponse = self.get_json(&url).await?;
+
+        log::debug!("{response:#?}");
+
+        // Bugzilla lists the comments under the numeric ID of the bug, even if the request
+        // used an alias. We requested a single bug, so take the first entry.
+        response
+            .bugs
+            .into_values()
+            .next()
+            .map(|bug| bug.comments)
+            .ok_or(BugzillaQueryError::NoBugs)
+    }
+
+    /// Access a single comment by its ID, which is unique across all bugs.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotFound` if the comment does not exist, or another error
+    /// if the request fails.
+    pub async fn comment(&self, comment_id: &str) -> Result<Comment, BugzillaQueryError> {
+        let url = self.rest_url(&format!("bug/comment/{comment_id}"));
+        let not_found = || BugzillaQueryError::NotFound {
+            id: comment_id.to_string(),
+        };
+
+        // Bugzilla reports a nonexistent comment with the error code 111.
+        let response: SingleCommentResponse = match self.get_json(&url).await {
+            Err(BugzillaQueryError::Api { code: 111, .. }) => return Err(not_found()),
+            result => result?,
+        };
+
+        response.comments.into_values().next().ok_or_else(not_found)
+    }
+
+    /// Access the public comments in a bug, leaving out the private ones.
+    /// The bug description is included if it's public. Use this for public-facing content,
+    /// such as a changelog, even if your credentials can access private comments.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails, or if Bugzilla returns
+    /// no comments for the bug.
+    pub async fn public_comments(&self, id: &str) -> Result<Vec<Comment>, BugzillaQueryError> {
+        let comments = self.comments(id).await?;
+
+        Ok(comments.into_iter().filter(Comment::is_public).collect())
+    }
+
+    /// Access all attachments in a bug, including their data.
+    ///
+    /// A bug without attachments results in an empty list, rather than an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails.
+    pub async fn attachments(&self, id: &str) -> Result<Vec<Attachment>, BugzillaQueryError> {
+        let url = self.rest_url(&format!("bug/{id}/attachment"));
+
+        let response: AttachmentsResponse = self.get_json(&url).await?;
+
+        // Unlike in the case of bugs, no attachments is a perfectly normal result.
+        Ok(response.bugs.into_values().next().unwrap_or_default())
+    }
+
+    /// Access a single attachment by its ID, including its data.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotFound` if the attachment does not exist, or another error
+    /// if the request fails.
+    pub async fn attachment(&self, attachment_id: &str) -> Result<Attachment, BugzillaQueryError> {
+        let url = self.rest_url(&format!("bug/attachment/{attachment_id}"));
+        let not_found = || BugzillaQueryError::NotFound {
+            id: attachment_id.to_string(),
+        };
+
+        // Bugzilla reports a nonexistent attachment with the error code 100.
+        let response: SingleAttachmentResponse = match self.get_json(&url).await {
+            Err(BugzillaQueryError::Api { code: 100, .. }) => return Err(not_found()),
+            result => result?,
+        };
+
+        response
+            .attachments
+            .into_values()
+            .next()
+            .ok_or_else(not_found)
+    }
+
+    /// Access the history of changes in a bug, in chronological order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails, or if Bugzilla returns
+    /// no history for the bug.
+    pub async fn history(&self, id: &str) -> Result<Vec<HistoryEntry>, BugzillaQueryError> {
+        let url = self.rest_url(&format!("bug/{id}/history"));
+
+        let response: HistoryResponse = self.get_json(&url).await?;
+
+        log::debug!("{response:#?}");
+
+        // We requested a single bug, so take the first entry.
+        response
+            .bugs
+            .into_iter()
+            .next()
+            .map(|bug| bug.history)
+            .ok_or(BugzillaQueryError::NoBugs)
+    }
+
+    /// Create a new bug and return its ID.
+    ///
+    /// This is a change, so it requires authentication.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Unauthorized` if the authentication is anonymous, `Api` if Bugzilla
+    /// rejects the bug, such as because of a missing required field,
+    /// or another error if the request fails.
+    pub async fn create_bug(&self, new: NewBug) -> Result<i32, BugzillaQueryError> {
+        let url = self.rest_url("bug");
+
+        let response: CreatedResponse = self.send_json(reqwest::Method::POST, &url, &new).await?;
+        log::debug!("Created bug {}.", response.id);
+
+        Ok(response.id)
+    }
+
+    /// Change the fields of an existing bug. Only the fields set in `update` change.
+    ///
+    /// This is a change, so it requires authentication.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Unauthorized` if the authentication is anonymous, `Api` if Bugzilla
+    /// rejects the changes, such as because of an invalid status,
+    /// or another error if the request fails.
+    pub async fn update_bug(&self, id: &str, update: BugUpdate) -> Result<(), BugzillaQueryError> {
+        let url = self.rest_url(&format!("bug/{id}"));
+
+        // The response lists the changes, which the caller already knows.
+        // Parsing it still reports an error that Bugzilla sends with a successful status.
+        let _: UpdatedResponse = self.send_json(reqwest::Method::PUT, &url, &update).await?;
+        log::debug!("Updated bug {id}.");
+
+        Ok(())
+    }
+
+    /// Add a comment to a bug and return the ID of the new comment.
+    /// A private comment is only visible to the members of the insider group.
+    ///
+    /// This is a change, so it requires authentication.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Unauthorized` if the authentication is anonymous,
+    /// or another error if the request fails.
+    pub async fn add_comment(
+        &self,
+        id: &str,
+        text: &str,
+        is_private: bool,
+    ) -> Result<i32, BugzillaQueryError> {
+        let url = self.rest_url(&format!("bug/{id}/comment"));
+        let body = serde_json::json!({ "comment": text, "is_private": is_private });
+
+        let response: CreatedResponse = self.send_json(reqwest::Method::POST, &url, &body).await?;
+        log::debug!("Added comment {} to bug {id}.", response.id);
+
+        Ok(response.id)
+    }
+
+    /// Add your personal tags to a bug. Personal tags are only visible to you.
+    ///
+    /// This is a change, so it requires authentication.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Unauthorized` if the authentication is anonymous,
+    /// or another error if the request fails.
+    pub async fn add_tags(&self, id: &str, tags: &[&str]) -> Result<(), BugzillaQueryError> {
+        self.update_tags(id, &serde_json::json!({ "add": tags }))
+            .await
+    }
+
+    /// Remove your personal tags from a bug.
+    ///
+    /// This is a change, so it requires authentication.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Unauthorized` if the authentication is anonymous,
+    /// or another error if the request fails.
+    pub async fn remove_tags(&self, id: &str, tags: &[&str]) -> Result<(), BugzillaQueryError> {
+        self.update_tags(id, &serde_json::json!({ "remove": tags }))
+            .await
+    }
+
+    /// Change the personal tags of a bug using the tag update structure,
+    /// such as `{"add": ["tag"]}`.
+    async fn update_tags(&self, id: &str, update: &Value) -> Result<(), BugzillaQueryError> {
+        let url = self.rest_url(&format!("bug/{id}/tags"));
+        let body = serde_json::json!({ "ids": [id], "tags": update });
+
+        // The response lists the changed tags, but the caller already knows them.
+        // Parsing it still reports an error that Bugzilla sends with a successful status.
+        let _: TagsResponse = self.send_json(reqwest::Method::PUT, &url, &body).await?;
+
+        Ok(())
+    }
+
+    /// Access user accounts by their login names, such as `user@example.com`,
+    /// or by their numeric IDs. The list can combine names and IDs.
+    ///
+    /// Most Bugzilla instances only allow authenticated users to look up accounts.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails, such as `Unauthorized` without
+    /// valid credentials, or if any of the users does not exist.
+    pub async fn users(&self, names_or_ids: &[&str]) -> Result<Vec<User>, BugzillaQueryError> {
+        // As with bugs, no users on the input result in no users without a network request.
+        if names_or_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let params: Vec<(String, String)> = names_or_ids
+            .iter()
+            .map(|user| {
+                let key = if user.parse::<i32>().is_ok() {
+                    "ids"
+                } else {
+                    "names"
+                };
+                (key.to_string(), (*user).to_string())
+            })
+            .collect();
+        let url = format!("{}?{}", self.rest_url("user"), encode_params(&params));
+
+        let response: UsersResponse = self.get_json(&url).await?;
+
+        log::debug!("{response:#?}");
+
+        Ok(response.users)
+    }
+
+    /// Access the products that you can search and file bugs in, including their components.
+    ///
+    /// With anonymous access, the list contains only public products.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails.
+    pub async fn products(&self) -> Result<Vec<Product>, BugzillaQueryError> {
+        let url = format!("{}?type=accessible", self.rest_url("product"));
+
+        let response: ProductsResponse = self.get_json(&url).await?;
+
+        Ok(response.products)
+    }
+
+    /// Access the definition of a bug field on this instance, including its legal values.
+    /// The name can refer to a standard field, such as `bug_status`,
+    /// or to a custom field, such as `cf_doc_type`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails, or if the field does not exist.
+    pub async fn field(&self, name: &str) -> Result<FieldDefinition, BugzillaQueryError> {
+        let url = self.rest_url(&format!("field/bug/{name}"));
+
+        let response: FieldsResponse = self.get_json(&url).await?;
+
+        response
+            .fields
+            .into_iter()
+            .next()
+            .ok_or_else(|| BugzillaQueryError::UnknownField(name.to_string()))
     }
 }
@@ -0,0 +1,168 @@
+/*
+Copyright 2022 Marek Suchánek <msuchane@redhat.com>
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+//! This module provides a synchronous interface to Bugzilla, which blocks the current thread
+//! until the response arrives. It mirrors the async `BzInstance` and doesn't require
+//! an async runtime.
+//!
+//! Don't use this module from within an async runtime. Use the async `BzInstance` there instead.
+
+use crate::access::{
+    normalize_host, not_found_bug, parse_response, status_error, Auth, Method, Pagination,
+};
+use crate::bug_model::{Bug, Response};
+use crate::errors::BugzillaQueryError;
+
+/// Configuration and credentials to access a Bugzilla instance using blocking requests.
+pub struct BzInstance {
+    pub host: String,
+    pub auth: Auth,
+    pub pagination: Pagination,
+    pub included_fields: Vec<String>,
+    client: reqwest::blocking::Client,
+}
+
+impl BzInstance {
+    /// Create a new `BzInstance` struct using a host URL, with default values
+    /// for all options.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidHost` if the host isn't a valid `http` or `https` URL.
+    pub fn at(host: String) -> Result<Self, BugzillaQueryError> {
+        let client = reqwest::blocking::Client::new();
+
+        Ok(BzInstance {
+            host: normalize_host(host)?,
+            client,
+            included_fields: vec!["_default".to_string()],
+            auth: Auth::default(),
+            pagination: Pagination::default(),
+        })
+    }
+
+    /// Set the authentication method of this `BzInstance`.
+    #[must_use]
+    pub fn authenticate(mut self, auth: Auth) -> Self {
+        self.auth = auth;
+        self
+    }
+
+    /// Set the pagination method of this `BzInstance`.
+    #[must_use]
+    pub fn paginate(mut self, pagination: Pagination) -> Self {
+        self.pagination = pagination;
+        self
+    }
+
+    /// Set Bugzilla fields that this `BzInstance` will request, such as `flags`.
+    ///
+    /// By default, `BzInstance` requests the `_default` fields, and using this method
+    /// overwrites the default value. If you want to set fields in addition
+    /// to `_default`, specify `_default` in your list.
+    #[must_use]
+    pub fn include_fields(mut self, fields: Vec<String>) -> Self {
+        self.included_fields = fields;
+        self
+    }
+
+    /// Based on the request method, form a complete, absolute URL
+    /// to download the tickets from the REST API.
+    #[must_use]
+    fn path(&self, method: &Method) -> String {
+        let fields = if self.included_fields.is_empty() {
+            String::new()
+        } else {
+            format!("&include_fields={}", self.included_fields.join(","))
+        };
+
+        format!(
+            "{}/rest/bug?{}{}{}",
+            &self.host,
+            method.url_fragment(),
+            fields,
+            self.pagination.url_fragment()
+        )
+    }
+
+    /// Download the bugs from the specified URL using the configured authentication.
+    fn get_bugs(&self, url: &str) -> Result<Vec<Bug>, BugzillaQueryError> {
+        let request_builder = self.client.get(url);
+        let authenticated = match &self.auth {
+            Auth::Anonymous => request_builder,
+            Auth::ApiKey(key) => request_builder.bearer_auth(key),
+            Auth::Basic { user, password } => request_builder.basic_auth(user, Some(password)),
+            Auth::Token(token) => request_builder.query(&[("token", token)]),
+        };
+        let response = authenticated.send()?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(status_error(status, response.text()?));
+        }
+        let body = response.bytes()?;
+        let response: Response = parse_response(&body)?;
+
+        log::debug!("{response:#?}");
+
+        // The resulting list might be empty. In that case, return an error.
+        if response.bugs.is_empty() {
+            Err(BugzillaQueryError::NoBugs)
+        } else {
+            Ok(response.bugs)
+        }
+    }
+
+    /// Access several bugs by their IDs.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails, or if Bugzilla returns no bugs
+    /// for a non-empty list of IDs.
+    pub fn bugs(&self, ids: &[&str]) -> Result<Vec<Bug>, BugzillaQueryError> {
+        // If the user specifies no IDs, skip network requests and return no bugs.
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let url = self.path(&Method::Ids(ids));
+        self.get_bugs(&url)
+    }
+
+    /// Access a single bug by its ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotFound` if the bug does not exist, or another error if the request fails.
+    pub fn bug(&self, id: &str) -> Result<Bug, BugzillaQueryError> {
+        let bugs = not_found_bug(id, self.bugs(&[id]))?;
+
+        bugs.into_iter()
+            .next()
+            .ok_or_else(|| BugzillaQueryError::NotFound { id: id.to_string() })
+    }
+
+    /// Access bugs using a free-form Bugzilla search query.
+    ///
+    /// An example of a query: `component=rust&product=Fedora&version=36`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails, or if no bugs match the query.
+    pub fn search(&self, query: &str) -> Result<Vec<Bug>, BugzillaQueryError> {
+        let url = self.path(&Method::Search(query));
+        self.get_bugs(&url)
+    }
+}
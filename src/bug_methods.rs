@@ -14,23 +14,380 @@ See the License for the specific language governing permissions and
 limitations under the License.
 */
 
-use crate::bug_model::Bug;
+use std::collections::HashMap;
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde_json::Number;
+
+use crate::bug_model::{
+    Attachment, Bug, Comment, FieldChange, Flag, HistoryEntry, Resolution, SearchResult,
+    SeeAlsoLink, Status, TimeTracking,
+};
+use crate::errors::BugzillaQueryError;
+
+/// Convert a time-tracking field to hours as a decimal number.
+fn hours(time: Option<&Number>) -> Option<f64> {
+    time.and_then(Number::as_f64)
+}
+
+/// Check if the path segment is a Jira issue key, such as `RHEL-1234`.
+fn is_jira_key(segment: &str) -> bool {
+    match segment.split_once('-') {
+        Some((project, number)) => {
+            project.starts_with(|c: char| c.is_ascii_uppercase())
+                && project
+                    .chars()
+                    .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+                && !number.is_empty()
+                && number.chars().all(|c| c.is_ascii_digit())
+        }
+        None => false,
+    }
+}
+
+impl SeeAlsoLink {
+    /// Classify the link by parsing its URL.
+    fn parse(link: &str) -> Self {
+        let url = match reqwest::Url::parse(link) {
+            Ok(url) => url,
+            Err(_) => return Self::Other(link.to_string()),
+        };
+        let host = match url.host_str() {
+            Some(host) => host,
+            None => return Self::Other(link.to_string()),
+        };
+
+        // A Bugzilla bug URL looks like `https://host/show_bug.cgi?id=1234`.
+        if url.path().ends_with("/show_bug.cgi") {
+            let id = url
+                .query_pairs()
+                .find(|(key, _)| key == "id")
+                .and_then(|(_, id)| id.parse().ok());
+            if let Some(id) = id {
+                return Self::Bugzilla {
+                    host: host.to_string(),
+                    id,
+                };
+            }
+        }
+
+        if host == "github.com" || host.ends_with(".github.com") {
+            return Self::GitHub {
+                url: link.to_string(),
+            };
+        }
+
+        // A Jira issue URL looks like `https://host/browse/KEY-1234`.
+        let segments: Vec<&str> = url.path_segments().into_iter().flatten().collect();
+        if let [.., "browse", key] = segments.as_slice() {
+            if is_jira_key(key) {
+                return Self::Jira {
+                    url: link.to_string(),
+                };
+            }
+        }
+
+        Self::Other(link.to_string())
+    }
+}
 
 impl Bug {
+    /// Returns the status of the bug as Bugzilla reports it, such as `CLOSED`.
+    /// If the response didn't include the status, the function returns None.
+    #[must_use]
+    pub fn status_raw(&self) -> Option<&str> {
+        self.status.as_ref().map(Status::as_str)
+    }
+
+    /// Returns the resolution of the bug as Bugzilla reports it, such as `WONTFIX`.
+    /// Open bugs have an empty resolution. If the response didn't include the resolution,
+    /// the function returns None.
+    #[must_use]
+    pub fn resolution_raw(&self) -> Option<&str> {
+        self.resolution.as_ref().map(Resolution::as_str)
+    }
+
+    /// Returns true if the bug is closed as a duplicate of another bug.
+    /// The comparison ignores case.
+    #[must_use]
+    pub fn is_duplicate(&self) -> bool {
+        self.resolution_raw().map_or(false, |resolution| {
+            resolution.eq_ignore_ascii_case("DUPLICATE")
+        })
+    }
+
+    /// Returns true if the bug has a resolution, such as `FIXED`, regardless of its status.
+    /// Returns false if the response didn't include the resolution.
+    #[must_use]
+    pub fn is_resolved(&self) -> bool {
+        self.resolution_raw()
+            .map_or(false, |resolution| !resolution.is_empty())
+    }
+
+    /// Returns true if the status of the bug is `VERIFIED`. The comparison ignores case.
+    #[must_use]
+    pub fn is_verified(&self) -> bool {
+        self.status_raw()
+            .map_or(false, |status| status.eq_ignore_ascii_case("VERIFIED"))
+    }
+
+    /// Returns true if the bug belongs to any group, which restricts who can see it.
+    /// Returns false if the response didn't include the groups.
+    #[must_use]
+    pub fn is_restricted(&self) -> bool {
+        !self.groups.is_empty()
+    }
+
+    /// Returns true if the bug belongs to the specified group, such as `redhat`.
+    #[must_use]
+    pub fn in_group(&self, group: &str) -> bool {
+        self.groups.iter().any(|g| g == group)
+    }
+
+    /// Parses the bracketed tokens in the status whiteboard, such as `[priority:high][blocker]`,
+    /// into keys and optional values: `("priority", Some("high"))` and `("blocker", None)`.
+    ///
+    /// The text outside of brackets and empty tokens are skipped. A token splits on the first `:`.
+    #[must_use]
+    pub fn whiteboard_tags(&self) -> Vec<(String, Option<String>)> {
+        let mut tags = Vec::new();
+        let mut rest = self.whiteboard.as_deref().unwrap_or_default();
+
+        while let Some(start) = rest.find('[') {
+            let after_start = &rest[start + 1..];
+            let end = match after_start.find(']') {
+                Some(end) => end,
+                None => break,
+            };
+            // A nested opening bracket starts a new token instead.
+            let token = after_start[..end]
+                .rsplit('[')
+                .next()
+                .unwrap_or_default()
+                .trim();
+            rest = &after_start[end + 1..];
+
+            if token.is_empty() {
+                continue;
+            }
+            let tag = match token.split_once(':') {
+                Some((key, value)) => {
+                    let value = value.trim();
+                    let value = (!value.is_empty()).then(|| value.to_string());
+                    (key.trim().to_string(), value)
+                }
+                None => (token.to_string(), None),
+            };
+            tags.push(tag);
+        }
+
+        tags
+    }
+
+    /// Deserializes the fields that the bug model doesn't cover, such as custom fields
+    /// specific to your Bugzilla instance, into your own struct.
+    ///
+    /// For example, define `#[derive(Deserialize)] struct MyFields { cf_release_notes: Option<String> }`
+    /// and call `bug.custom_fields::<MyFields>()`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the fields don't match the struct.
+    pub fn custom_fields<T: DeserializeOwned>(&self) -> Result<T, BugzillaQueryError> {
+        T::deserialize(&self.extra).map_err(BugzillaQueryError::CustomFields)
+    }
+
     /// Returns a the value of the flag corresponding to the flag name.
     /// If no flag by that name is set in the bug, the function returns None.
     ///
-    /// # Panics
+    /// The function also returns None if flags are not available at all.
+    /// Enable flags when accessing the Bugzilla instance with `BzInstance::with_flags`.
     ///
-    /// The function panics if flags are not available at all.
-    /// Enable flags when accessing the Bugzilla instance.
+    /// This is the same as `try_get_flag`, kept for compatibility.
     #[must_use]
     pub fn get_flag<'a>(&'a self, name: &str) -> Option<&'a str> {
-        let flags = self
-            .flags
-            .as_ref()
-            .expect("The bug has no flags. Enable flags when accessing Bugzilla.");
-        let flag = flags.iter().find(|f| f.name == name)?;
+        self.try_get_flag(name)
+    }
+
+    /// Returns the value of the flag corresponding to the flag name.
+    /// Returns None if no flag by that name is set in the bug, or if flags are not available.
+    /// To distinguish these cases, use `flags_loaded`.
+    #[must_use]
+    pub fn try_get_flag<'a>(&'a self, name: &str) -> Option<&'a str> {
+        let flag = self.flags.as_ref()?.iter().find(|f| f.name == name)?;
         Some(&flag.status)
     }
+
+    /// Returns true if the response included flags, even if the bug has no flags set.
+    /// Enable flags when accessing the Bugzilla instance with `BzInstance::with_flags`.
+    #[must_use]
+    pub fn flags_loaded(&self) -> bool {
+        self.flags.is_some()
+    }
+
+    /// Returns all flags in the bug. The list is empty if flags are not available.
+    /// To distinguish that from a bug without flags, use `flags_loaded`.
+    #[must_use]
+    pub fn flags(&self) -> &[Flag] {
+        self.flags.as_deref().unwrap_or_default()
+    }
+
+    /// Returns all flags in the bug as a map from the flag name to the flag value.
+    ///
+    /// The map is empty if flags are not available.
+    /// Enable flags when accessing the Bugzilla instance with `BzInstance::with_flags`.
+    #[must_use]
+    pub fn flags_map(&self) -> HashMap<&str, &str> {
+        self.flags
+            .iter()
+            .flatten()
+            .map(|flag| (flag.name.as_str(), flag.status.as_str()))
+            .collect()
+    }
+
+    /// Returns the links in the `see_also` field, classified by where they point.
+    /// If the response didn't include the field, the list is empty.
+    #[must_use]
+    pub fn see_also_links(&self) -> Vec<SeeAlsoLink> {
+        self.see_also
+            .iter()
+            .flatten()
+            .map(|link| SeeAlsoLink::parse(link))
+            .collect()
+    }
+
+    /// Returns the original estimate of the time to fix the bug, in hours.
+    /// If the response didn't include the field, the function returns None.
+    #[must_use]
+    pub fn estimated_time_hours(&self) -> Option<f64> {
+        hours(self.estimated_time.as_ref())
+    }
+
+    /// Returns the time that fixing the bug still takes, in hours.
+    /// If the response didn't include the field, the function returns None.
+    #[must_use]
+    pub fn remaining_time_hours(&self) -> Option<f64> {
+        hours(self.remaining_time.as_ref())
+    }
+
+    /// Returns the total time that people worked on the bug, in hours.
+    /// If the response didn't include the field, the function returns None.
+    #[must_use]
+    pub fn actual_time_hours(&self) -> Option<f64> {
+        hours(self.actual_time.as_ref())
+    }
+
+    /// Returns the time that people worked on the bug in the last change, in hours.
+    /// If the response didn't include the field, the function returns None.
+    #[must_use]
+    pub fn work_time_hours(&self) -> Option<f64> {
+        hours(self.work_time.as_ref())
+    }
+
+    /// Returns all time-tracking fields of the bug, in hours.
+    #[must_use]
+    pub fn time_tracking(&self) -> TimeTracking {
+        TimeTracking {
+            estimated_time: self.estimated_time_hours(),
+            remaining_time: self.remaining_time_hours(),
+            actual_time: self.actual_time_hours(),
+            work_time: self.work_time_hours(),
+        }
+    }
+}
+
+impl SearchResult {
+    /// Returns true if more bugs match the search than the result contains
+    /// up to and including this page, such as because the default limit of the instance
+    /// truncated the search. Returns false if Bugzilla didn't report `total_matches`.
+    #[must_use]
+    pub fn is_truncated(&self) -> bool {
+        let offset = self.offset.unwrap_or_default() as usize;
+        self.total_matches.map_or(false, |total| {
+            offset + self.bugs.len() + self.skipped < total as usize
+        })
+    }
+}
+
+impl Comment {
+    /// Returns true if the comment is visible to everyone, rather than
+    /// only to the members of the insider group.
+    #[must_use]
+    pub fn is_public(&self) -> bool {
+        !self.is_private
+    }
+
+    /// Returns true if the comment has the specified tag, such as `spam`.
+    /// Bugzilla compares comment tags regardless of case.
+    #[must_use]
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+}
+
+impl Flag {
+    /// Returns true if the flag is granted, with the `+` status.
+    #[must_use]
+    pub fn is_granted(&self) -> bool {
+        self.status == "+"
+    }
+
+    /// Returns true if the flag is denied, with the `-` status.
+    #[must_use]
+    pub fn is_denied(&self) -> bool {
+        self.status == "-"
+    }
+
+    /// Returns true if the flag is requested, with the `?` status.
+    /// The `requestee` field lists the user who's asked to set the flag, if any.
+    #[must_use]
+    pub fn is_requested(&self) -> bool {
+        self.status == "?"
+    }
+}
+
+impl Attachment {
+    /// Returns the content of the attachment, decoded from base64 to raw bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the response did not include the attachment data,
+    /// or if the data is not valid base64.
+    pub fn decoded_data(&self) -> Result<Vec<u8>, BugzillaQueryError> {
+        let data = self
+            .data
+            .as_ref()
+            .ok_or(BugzillaQueryError::MissingAttachmentData)?;
+        let decoded = STANDARD.decode(data)?;
+        Ok(decoded)
+    }
+}
+
+/// Returns the latest `last_change_time` of the bugs, or `None` if no bug includes the field.
+///
+/// Use this as the watermark for the next call to `BzInstance::search_changed_since`.
+/// Because that search includes the bugs that changed at the watermark itself,
+/// the latest bug appears again in the next results.
+#[must_use]
+pub fn latest_change_time(bugs: &[Bug]) -> Option<DateTime<Utc>> {
+    bugs.iter().filter_map(|bug| bug.last_change_time).max()
+}
+
+/// Returns the field changes in the history that happened in the time window,
+/// which includes `from` and excludes `to`. Adjacent windows therefore don't overlap.
+///
+/// The changes keep the order of the history.
+#[must_use]
+pub fn changes_between(
+    history: &[HistoryEntry],
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Vec<&FieldChange> {
+    history
+        .iter()
+        .filter(|entry| from <= entry.when && entry.when < to)
+        .flat_map(|entry| &entry.changes)
+        .collect()
 }
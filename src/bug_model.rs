@@ -18,25 +18,180 @@ limitations under the License.
 //! Any extra fields that come from a custom Bugzilla configuration are captured
 //! in the `extra` hash map in the parent struct.
 
+use std::collections::HashMap;
 use std::fmt;
+use std::iter::{Chain, Map};
+use std::{option, slice, vec};
 
-use chrono::{DateTime, NaiveDate, Utc};
-use serde::Deserialize;
-use serde_json::Value;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use serde::{de, Deserialize, Deserializer, Serialize};
+use serde_json::{Number, Value};
+
+/// Parse a timestamp in one of the formats that Bugzilla instances use:
+///
+/// * RFC 3339, such as `2022-05-01T12:30:00Z` or `2022-05-01T14:30:00+02:00`
+/// * Without the time zone, such as `2022-05-01T12:30:00`, which is in UTC
+/// * With a space instead of `T`, such as `2022-05-01 12:30:00`, which is in UTC
+fn parse_timestamp(timestamp: &str) -> Result<DateTime<Utc>, chrono::ParseError> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .map(|time| time.with_timezone(&Utc))
+        .or_else(|_| {
+            NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%dT%H:%M:%S")
+                .map(|time| Utc.from_utc_datetime(&time))
+        })
+        .or_else(|_| {
+            NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S")
+                .map(|time| Utc.from_utc_datetime(&time))
+        })
+}
+
+/// Deserialize a timestamp in any of the formats that `parse_timestamp` accepts.
+fn deserialize_timestamp<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let timestamp = String::deserialize(deserializer)?;
+    parse_timestamp(&timestamp).map_err(de::Error::custom)
+}
+
+/// Deserialize an optional timestamp in any of the formats that `parse_timestamp` accepts,
+/// and treat an empty string as a missing timestamp.
+fn deserialize_optional_timestamp<'de, D>(
+    deserializer: D,
+) -> Result<Option<DateTime<Utc>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<String>::deserialize(deserializer)? {
+        Some(timestamp) if !timestamp.is_empty() => parse_timestamp(&timestamp)
+            .map(Some)
+            .map_err(de::Error::custom),
+        _ => Ok(None),
+    }
+}
+
+/// Deserialize an optional date in the `YYYY-MM-DD` format.
+/// Some instances send an empty string instead of `null` if the date is not set,
+/// so treat an empty string as a missing date.
+fn deserialize_optional_date<'de, D>(deserializer: D) -> Result<Option<NaiveDate>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<String>::deserialize(deserializer)? {
+        Some(date) if !date.is_empty() => NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+            .map(Some)
+            .map_err(de::Error::custom),
+        _ => Ok(None),
+    }
+}
 
 /// The response from Bugzilla, which includes the list of requested bugs
 /// and some additional metadata.
-#[derive(Clone, Debug, Deserialize)]
+// The `extra` field is not used now, but it completes the model of the response.
+#[allow(dead_code)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Response {
     pub offset: Option<u32>,
     pub limit: Option<String>,
     pub total_matches: Option<u32>,
     pub bugs: Vec<Bug>,
+    /// The number of bugs that failed to deserialize and were skipped in the lenient mode.
+    #[serde(skip)]
+    pub skipped: usize,
     #[serde(flatten)]
     pub extra: Value,
 }
 
+/// A response from Bugzilla with the bugs left as raw JSON objects.
+#[derive(Clone, Debug, Deserialize)]
+pub struct RawResponse {
+    pub offset: Option<u32>,
+    pub limit: Option<String>,
+    pub total_matches: Option<u32>,
+    pub bugs: Vec<Value>,
+    #[serde(flatten)]
+    pub extra: Value,
+}
+
+impl RawResponse {
+    /// Deserialize each bug separately, skipping and logging the bugs that fail.
+    pub fn into_lenient(self) -> Response {
+        let total = self.bugs.len();
+        let bugs: Vec<Bug> = self
+            .bugs
+            .into_iter()
+            .filter_map(|bug| {
+                let id = bug.get("id").cloned().unwrap_or(Value::Null);
+                match serde_json::from_value(bug) {
+                    Ok(bug) => Some(bug),
+                    Err(error) => {
+                        log::warn!("Skipping bug {id} that failed to deserialize: {error}");
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        Response {
+            offset: self.offset,
+            limit: self.limit,
+            total_matches: self.total_matches,
+            skipped: total - bugs.len(),
+            bugs,
+            extra: self.extra,
+        }
+    }
+}
+
+/// The bugs that match a search, along with the pagination metadata
+/// that Bugzilla reported for the search.
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+pub struct SearchResult {
+    pub bugs: Vec<Bug>,
+    /// The number of matching bugs that Bugzilla skipped before the returned bugs.
+    pub offset: Option<u32>,
+    pub limit: Option<String>,
+    /// The number of all bugs that match the search, regardless of pagination.
+    pub total_matches: Option<u32>,
+    /// The number of bugs that failed to deserialize and were skipped in the lenient mode.
+    pub skipped: usize,
+}
+
+impl From<Response> for SearchResult {
+    fn from(response: Response) -> Self {
+        Self {
+            bugs: response.bugs,
+            offset: response.offset,
+            limit: response.limit,
+            total_matches: response.total_matches,
+            skipped: response.skipped,
+        }
+    }
+}
+
+/// The bugs that a dependency traversal reached from the root bug,
+/// along with the dependencies among them.
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+pub struct DependencyGraph {
+    /// The ID of the bug where the traversal started.
+    pub root: i32,
+    /// All reached bugs, including the root bug, by their IDs.
+    pub bugs: HashMap<i32, Bug>,
+    /// The IDs of the reached bugs that each reached bug depends on or blocks,
+    /// in the traversed direction.
+    pub neighbors: HashMap<i32, Vec<i32>>,
+}
+
+/// The response from Bugzilla to a successful login.
+#[derive(Clone, Deserialize)]
+pub struct LoginResponse {
+    pub id: i32,
+    pub token: String,
+}
+
 /// An error report from Bugzilla.
+// The `extra` field is not used now, but it completes the model of the response.
+#[allow(dead_code)]
 #[derive(Clone, Debug, Deserialize)]
 pub struct BugzillaError {
     pub error: bool,
@@ -47,9 +202,10 @@ pub struct BugzillaError {
 }
 
 /// Certain fields can appear as a single, optional string or a list of strings based on the Bugzilla instance and its configuration.
-#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
 #[serde(untagged)]
 pub enum OneOrMany {
+    #[default]
     None,
     One(String),
     Many(Vec<String>),
@@ -57,6 +213,7 @@ pub enum OneOrMany {
 
 impl OneOrMany {
     /// Regardless of the Bugzilla instance configuration, list all items in a vector: empty, one item, or more items.
+    #[must_use]
     pub fn into_vec(self) -> Vec<String> {
         match self {
             Self::None => Vec::new(),
@@ -64,6 +221,92 @@ impl OneOrMany {
             Self::Many(v) => v,
         }
     }
+
+    /// Check if any of the items is equal to the specified item.
+    #[must_use]
+    pub fn contains(&self, item: &str) -> bool {
+        match self {
+            Self::None => false,
+            Self::One(s) => s == item,
+            Self::Many(v) => v.iter().any(|s| s == item),
+        }
+    }
+
+    /// Returns the primary item: the single item, or the first of several items.
+    #[must_use]
+    pub fn first(&self) -> Option<&str> {
+        match self {
+            Self::None => None,
+            Self::One(s) => Some(s),
+            Self::Many(v) => v.first().map(String::as_str),
+        }
+    }
+
+    /// Check if there are no items.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Self::None => true,
+            Self::One(_) => false,
+            Self::Many(v) => v.is_empty(),
+        }
+    }
+
+    /// Iterate over the items without allocating a vector.
+    #[must_use]
+    pub fn iter(&self) -> <&Self as IntoIterator>::IntoIter {
+        self.into_iter()
+    }
+}
+
+impl IntoIterator for OneOrMany {
+    type Item = String;
+    type IntoIter = Chain<option::IntoIter<String>, vec::IntoIter<String>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        // An empty vector doesn't allocate.
+        let (one, many) = match self {
+            Self::None => (None, Vec::new()),
+            Self::One(s) => (Some(s), Vec::new()),
+            Self::Many(v) => (None, v),
+        };
+        one.into_iter().chain(many)
+    }
+}
+
+impl<'a> IntoIterator for &'a OneOrMany {
+    type Item = &'a str;
+    type IntoIter = Map<
+        Chain<option::IntoIter<&'a String>, slice::Iter<'a, String>>,
+        fn(&'a String) -> &'a str,
+    >;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let (one, many): (Option<&String>, &[String]) = match self {
+            OneOrMany::None => (None, &[]),
+            OneOrMany::One(s) => (Some(s), &[]),
+            OneOrMany::Many(v) => (None, v),
+        };
+        one.into_iter().chain(many).map(String::as_str)
+    }
+}
+
+/// An empty list converts to `None`, and any other list to `Many`.
+impl From<Vec<String>> for OneOrMany {
+    fn from(items: Vec<String>) -> Self {
+        if items.is_empty() {
+            Self::None
+        } else {
+            Self::Many(items)
+        }
+    }
+}
+
+/// No items collect to `None`, and any other items to `Many`.
+impl FromIterator<String> for OneOrMany {
+    fn from_iter<I: IntoIterator<Item = String>>(iter: I) -> Self {
+        Self::from(iter.into_iter().collect::<Vec<_>>())
+    }
 }
 
 /// Some Bugzilla instances set the component as a single string, some use a list of components.
@@ -76,55 +319,75 @@ pub type Version = OneOrMany;
 pub type Alias = OneOrMany;
 
 /// The representation of a single Bugzilla bug with all its fields.
-#[allow(clippy::struct_excessive_bools)]
-#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+///
+/// Only the `id` field is always present. The other fields are missing if the request
+/// excluded them, for example by setting `BzInstance::include_fields`.
+/// Missing list fields are empty.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
 pub struct Bug {
+    #[serde(default)]
     pub alias: Alias,
-    pub op_sys: String,
-    pub classification: String,
+    pub op_sys: Option<String>,
+    pub classification: Option<String>,
     pub id: i32,
-    pub url: String,
-    pub creator: String,
-    pub creator_detail: User,
-    pub summary: String,
-    pub status: String,
-    pub estimated_time: Option<i64>,
-    pub target_milestone: String,
+    pub url: Option<String>,
+    pub creator: Option<String>,
+    pub creator_detail: Option<User>,
+    pub summary: Option<String>,
+    pub status: Option<Status>,
+    /// The time that fixing the bug takes in the original estimate, in hours.
+    /// Bugzilla reports the time as a decimal number, so use `estimated_time_hours` to read it.
+    pub estimated_time: Option<Number>,
+    pub target_milestone: Option<String>,
+    #[serde(default)]
     pub cc: Vec<String>,
+    #[serde(default)]
     pub cc_detail: Vec<User>,
-    pub is_open: bool,
-    pub is_creator_accessible: bool,
+    pub is_open: Option<bool>,
+    pub is_creator_accessible: Option<bool>,
     pub docs_contact: Option<String>,
     pub docs_contact_detail: Option<User>,
-    pub assigned_to: String,
-    pub assigned_to_detail: User,
-    pub resolution: String,
-    pub severity: String,
-    pub product: String,
-    pub platform: String,
-    pub last_change_time: DateTime<Utc>,
-    pub remaining_time: Option<i64>,
-    pub priority: String,
-    pub whiteboard: String,
-    pub creation_time: DateTime<Utc>,
-    pub is_confirmed: bool,
-    pub qa_contact: String,
+    pub assigned_to: Option<String>,
+    pub assigned_to_detail: Option<User>,
+    pub resolution: Option<Resolution>,
+    pub severity: Option<String>,
+    pub product: Option<String>,
+    pub platform: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_optional_timestamp")]
+    pub last_change_time: Option<DateTime<Utc>>,
+    /// The time that fixing the bug still takes, in hours.
+    pub remaining_time: Option<Number>,
+    pub priority: Option<String>,
+    pub whiteboard: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_optional_timestamp")]
+    pub creation_time: Option<DateTime<Utc>>,
+    pub is_confirmed: Option<bool>,
+    pub qa_contact: Option<String>,
     pub qa_contact_detail: Option<User>,
     pub dupe_of: Option<i32>,
     pub target_release: Option<Version>,
-    pub actual_time: Option<i64>,
+    /// The total time that people worked on the bug, in hours.
+    pub actual_time: Option<Number>,
+    #[serde(default)]
     pub component: Component,
-    pub is_cc_accessible: bool,
+    pub is_cc_accessible: Option<bool>,
+    #[serde(default)]
     pub version: Version,
+    #[serde(default)]
     pub keywords: Vec<String>,
+    #[serde(default)]
     pub depends_on: Vec<i32>,
+    #[serde(default)]
     pub blocks: Vec<i32>,
     pub see_also: Option<Vec<String>>,
+    #[serde(default)]
     pub groups: Vec<String>,
     /// Bugzilla stores `deadline` only as `YYYY-MM-DD`, so it can't deserialize to full `DateTime`.
+    #[serde(default, deserialize_with = "deserialize_optional_date")]
     pub deadline: Option<NaiveDate>,
     pub update_token: Option<String>,
-    pub work_time: Option<i64>,
+    /// The time that people worked on the bug in the last change, in hours.
+    pub work_time: Option<Number>,
     // Not part of the default response:
     pub flags: Option<Vec<Flag>>,
     pub tags: Option<Vec<String>>,
@@ -133,8 +396,171 @@ pub struct Bug {
     pub extra: Value,
 }
 
+/// A link from the `see_also` field of a bug, classified by where it points.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SeeAlsoLink {
+    /// A bug on a Bugzilla instance, such as `https://bugzilla.redhat.com/show_bug.cgi?id=1906883`.
+    Bugzilla { host: String, id: i32 },
+    /// An issue or a pull request on GitHub.
+    GitHub { url: String },
+    /// An issue in Jira, such as `https://issues.redhat.com/browse/RHEL-1234`.
+    Jira { url: String },
+    /// Any other link, including a link that isn't a valid URL.
+    Other(String),
+}
+
+/// The time-tracking fields of a bug, in hours. Bugzilla only reports these fields
+/// to users in the time-tracking group, so any of them can be missing.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TimeTracking {
+    pub estimated_time: Option<f64>,
+    pub remaining_time: Option<f64>,
+    pub actual_time: Option<f64>,
+    pub work_time: Option<f64>,
+}
+
+impl fmt::Display for Bug {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Displays the bug in the format of `#id [status] summary`,
+        // skipping the fields that the response didn't include.
+        write!(f, "#{}", self.id)?;
+        if let Some(status) = &self.status {
+            write!(f, " [{status}]")?;
+        }
+        if let Some(summary) = &self.summary {
+            write!(f, " {summary}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The status of a bug. Instances can define their own statuses,
+/// which are available as the `Custom` variant.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[serde(from = "String", into = "String")]
+pub enum Status {
+    Unconfirmed,
+    New,
+    Assigned,
+    InProgress,
+    Resolved,
+    Verified,
+    Closed,
+    Custom(String),
+}
+
+impl Status {
+    /// Returns the status as Bugzilla reports it, such as `CLOSED`.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Unconfirmed => "UNCONFIRMED",
+            Self::New => "NEW",
+            Self::Assigned => "ASSIGNED",
+            Self::InProgress => "IN_PROGRESS",
+            Self::Resolved => "RESOLVED",
+            Self::Verified => "VERIFIED",
+            Self::Closed => "CLOSED",
+            Self::Custom(status) => status,
+        }
+    }
+}
+
+impl From<String> for Status {
+    fn from(status: String) -> Self {
+        match status.as_str() {
+            "UNCONFIRMED" => Self::Unconfirmed,
+            "NEW" => Self::New,
+            "ASSIGNED" => Self::Assigned,
+            "IN_PROGRESS" => Self::InProgress,
+            "RESOLVED" => Self::Resolved,
+            "VERIFIED" => Self::Verified,
+            "CLOSED" => Self::Closed,
+            _ => Self::Custom(status),
+        }
+    }
+}
+
+impl From<Status> for String {
+    fn from(status: Status) -> Self {
+        match status {
+            Status::Custom(status) => status,
+            other => other.as_str().to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// The resolution of a bug. Open bugs have the `Empty` resolution.
+/// Instances can define their own resolutions, which are available as the `Custom` variant.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[serde(from = "String", into = "String")]
+pub enum Resolution {
+    Empty,
+    Fixed,
+    Invalid,
+    WontFix,
+    Duplicate,
+    WorksForMe,
+    Moved,
+    Custom(String),
+}
+
+impl Resolution {
+    /// Returns the resolution as Bugzilla reports it, such as `WONTFIX`.
+    /// The `Empty` resolution is an empty string.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Empty => "",
+            Self::Fixed => "FIXED",
+            Self::Invalid => "INVALID",
+            Self::WontFix => "WONTFIX",
+            Self::Duplicate => "DUPLICATE",
+            Self::WorksForMe => "WORKSFORME",
+            Self::Moved => "MOVED",
+            Self::Custom(resolution) => resolution,
+        }
+    }
+}
+
+impl From<String> for Resolution {
+    fn from(resolution: String) -> Self {
+        match resolution.as_str() {
+            "" => Self::Empty,
+            "FIXED" => Self::Fixed,
+            "INVALID" => Self::Invalid,
+            "WONTFIX" => Self::WontFix,
+            "DUPLICATE" => Self::Duplicate,
+            "WORKSFORME" => Self::WorksForMe,
+            "MOVED" => Self::Moved,
+            _ => Self::Custom(resolution),
+        }
+    }
+}
+
+impl From<Resolution> for String {
+    fn from(resolution: Resolution) -> Self {
+        match resolution {
+            Resolution::Custom(resolution) => resolution,
+            other => other.as_str().to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Resolution {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
 /// The representation of a Bugzilla user account.
-#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
 pub struct User {
     pub email: String,
     pub id: i32,
@@ -144,14 +570,22 @@ pub struct User {
     pub extra: Value,
 }
 
+/// The response from Bugzilla to a query for users.
+#[derive(Clone, Debug, Deserialize)]
+pub struct UsersResponse {
+    pub users: Vec<User>,
+}
+
 /// The representation of a flag in a bug.
 /// A flag resembles a hash map entry, where `flag.name` is the key
 /// and `flag.status` is the value.
-#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
 pub struct Flag {
     pub id: i32,
     pub type_id: i32,
+    #[serde(deserialize_with = "deserialize_timestamp")]
     pub creation_date: DateTime<Utc>,
+    #[serde(deserialize_with = "deserialize_timestamp")]
     pub modification_date: DateTime<Utc>,
     pub name: String,
     pub status: String,
@@ -167,3 +601,117 @@ impl fmt::Display for Flag {
         write!(f, "{}: {}", self.name, self.status)
     }
 }
+
+/// The response from Bugzilla to a request for comments, which lists the comments
+/// under the ID of each requested bug.
+#[derive(Clone, Debug, Deserialize)]
+pub struct CommentsResponse {
+    pub bugs: HashMap<String, BugComments>,
+}
+
+/// The response from Bugzilla to a request for comments by their IDs, which lists
+/// the comments under their IDs.
+#[derive(Clone, Debug, Deserialize)]
+pub struct SingleCommentResponse {
+    #[serde(default)]
+    pub comments: HashMap<String, Comment>,
+}
+
+/// The comments that belong to a single bug in the comments response.
+#[derive(Clone, Debug, Deserialize)]
+pub struct BugComments {
+    pub comments: Vec<Comment>,
+}
+
+/// The representation of a single comment in a bug.
+/// The comment with `count` 0 is the description of the bug.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Comment {
+    pub id: i32,
+    pub bug_id: i32,
+    pub attachment_id: Option<i32>,
+    pub count: i32,
+    pub text: String,
+    pub creator: String,
+    #[serde(deserialize_with = "deserialize_timestamp")]
+    pub time: DateTime<Utc>,
+    #[serde(deserialize_with = "deserialize_timestamp")]
+    pub creation_time: DateTime<Utc>,
+    pub is_private: bool,
+    /// The tags on the comment. Empty if the instance disables comment tagging.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(flatten)]
+    pub extra: Value,
+}
+
+/// The response from Bugzilla to a request for attachments, which lists the attachments
+/// under the ID of each requested bug.
+#[derive(Clone, Debug, Deserialize)]
+pub struct AttachmentsResponse {
+    pub bugs: HashMap<String, Vec<Attachment>>,
+}
+
+/// The response from Bugzilla to a request for attachments by their IDs, which lists
+/// the attachments under their IDs.
+#[derive(Clone, Debug, Deserialize)]
+pub struct SingleAttachmentResponse {
+    #[serde(default)]
+    pub attachments: HashMap<String, Attachment>,
+}
+
+/// The representation of a file attached to a bug.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Attachment {
+    pub id: i32,
+    pub bug_id: i32,
+    pub file_name: String,
+    pub summary: String,
+    pub content_type: String,
+    pub size: u64,
+    pub is_patch: bool,
+    pub is_obsolete: bool,
+    pub is_private: bool,
+    pub creator: String,
+    #[serde(deserialize_with = "deserialize_timestamp")]
+    pub creation_time: DateTime<Utc>,
+    #[serde(deserialize_with = "deserialize_timestamp")]
+    pub last_change_time: DateTime<Utc>,
+    /// The content of the attachment, encoded in base64.
+    /// Use `Attachment::decoded_data` to access the raw bytes.
+    pub data: Option<String>,
+    #[serde(flatten)]
+    pub extra: Value,
+}
+
+/// The response from Bugzilla to a request for the history of bugs.
+#[derive(Clone, Debug, Deserialize)]
+pub struct HistoryResponse {
+    pub bugs: Vec<BugHistory>,
+}
+
+/// The history of a single bug in the history response.
+#[derive(Clone, Debug, Deserialize)]
+pub struct BugHistory {
+    pub history: Vec<HistoryEntry>,
+}
+
+/// A set of changes that a user made to a bug at the same time.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct HistoryEntry {
+    #[serde(deserialize_with = "deserialize_timestamp")]
+    pub when: DateTime<Utc>,
+    pub who: String,
+    pub changes: Vec<FieldChange>,
+}
+
+/// A change of a single field in a bug.
+/// If the field holds a list, `removed` and `added` list only the changed items.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct FieldChange {
+    pub field_name: String,
+    pub removed: String,
+    pub added: String,
+    /// The ID of the attachment, if the change concerns an attachment rather than the bug itself.
+    pub attachment_id: Option<i32>,
+}
@@ -0,0 +1,62 @@
+/*
+Copyright 2022 Marek Suchánek <msuchane@redhat.com>
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+//! This module defines a trait for the methods that access bugs, so that code that depends
+//! on this crate can replace `BzInstance` with a fake implementation in its tests.
+
+use futures_util::future::BoxFuture;
+
+use crate::access::BzInstance;
+use crate::bug_model::Bug;
+use crate::errors::BugzillaQueryError;
+
+/// A source of bugs, such as a Bugzilla instance.
+///
+/// `BzInstance` implements this trait. Accept `impl BugSource` in your code
+/// to supply an in-memory implementation in tests instead.
+///
+/// The methods return boxed futures so that the trait works on older Rust versions.
+/// In an implementation, wrap an `async` block with `Box::pin`.
+pub trait BugSource {
+    /// Access a single bug by its ID. See `BzInstance::bug`.
+    fn bug<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<Bug, BugzillaQueryError>>;
+
+    /// Access several bugs by their IDs. See `BzInstance::bugs`.
+    fn bugs<'a>(
+        &'a self,
+        ids: &'a [&'a str],
+    ) -> BoxFuture<'a, Result<Vec<Bug>, BugzillaQueryError>>;
+
+    /// Access bugs using a free-form Bugzilla search query. See `BzInstance::search`.
+    fn search<'a>(&'a self, query: &'a str) -> BoxFuture<'a, Result<Vec<Bug>, BugzillaQueryError>>;
+}
+
+impl BugSource for BzInstance {
+    fn bug<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<Bug, BugzillaQueryError>> {
+        Box::pin(BzInstance::bug(self, id))
+    }
+
+    fn bugs<'a>(
+        &'a self,
+        ids: &'a [&'a str],
+    ) -> BoxFuture<'a, Result<Vec<Bug>, BugzillaQueryError>> {
+        Box::pin(BzInstance::bugs(self, ids))
+    }
+
+    fn search<'a>(&'a self, query: &'a str) -> BoxFuture<'a, Result<Vec<Bug>, BugzillaQueryError>> {
+        Box::pin(BzInstance::search(self, query))
+    }
+}
@@ -0,0 +1,54 @@
+/*
+Copyright 2022 Marek Suchánek <msuchane@redhat.com>
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+//! This module stores the responses from Bugzilla in memory, so that repeated requests
+//! can skip downloading the responses that haven't changed since.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// A response that Bugzilla identified with an `ETag` header.
+struct CachedResponse {
+    etag: String,
+    body: Vec<u8>,
+}
+
+/// An in-memory cache of responses, keyed by the URL of the request.
+#[derive(Default)]
+pub(crate) struct ResponseCache {
+    entries: Mutex<HashMap<String, CachedResponse>>,
+}
+
+impl ResponseCache {
+    /// The `ETag` of the cached response to the URL, if any.
+    pub(crate) fn etag(&self, url: &str) -> Option<String> {
+        let entries = self.entries.lock().ok()?;
+        entries.get(url).map(|response| response.etag.clone())
+    }
+
+    /// The body of the cached response to the URL, if any.
+    pub(crate) fn body(&self, url: &str) -> Option<Vec<u8>> {
+        let entries = self.entries.lock().ok()?;
+        entries.get(url).map(|response| response.body.clone())
+    }
+
+    /// Store the response to the URL, replacing a previous response.
+    pub(crate) fn store(&self, url: &str, etag: String, body: Vec<u8>) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert(url.to_string(), CachedResponse { etag, body });
+        }
+    }
+}
@@ -0,0 +1,131 @@
+/*
+Copyright 2022 Marek Suchánek <msuchane@redhat.com>
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+//! This module models the changes that you can send to Bugzilla, such as a new bug.
+//! Custom fields that come from a custom Bugzilla configuration go
+//! in the `extra` map in the parent struct.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// A new bug to create in Bugzilla.
+///
+/// Bugzilla requires the `product`, `component`, `summary`, `version`, `op_sys`,
+/// `platform`, and `severity` fields. Bugzilla sets the other fields to their default values
+/// unless you set them.
+#[derive(Clone, Debug, Default, Serialize, PartialEq, Eq)]
+pub struct NewBug {
+    pub product: String,
+    pub component: String,
+    pub summary: String,
+    pub version: String,
+    pub op_sys: String,
+    pub platform: String,
+    pub severity: String,
+    /// The text of the first comment in the bug.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Whether the first comment is private.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment_is_private: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assigned_to: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub qa_contact: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_milestone: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub alias: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cc: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub keywords: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<i32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub blocks: Vec<i32>,
+    /// Custom fields, such as `cf_doc_type`.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Changes to the fields of an existing bug. Only the fields that you set
+/// are sent to Bugzilla, and the other fields keep their current values.
+#[derive(Clone, Debug, Default, Serialize, PartialEq, Eq)]
+pub struct BugUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    /// The resolution of a closed bug, such as `NOTABUG`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolution: Option<String>,
+    /// The ID of the bug that this bug duplicates, with the `DUPLICATE` resolution.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dupe_of: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assigned_to: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub qa_contact: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub severity: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub product: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub component: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_milestone: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub whiteboard: Option<String>,
+    /// Custom fields, such as `cf_doc_type`.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// The response from Bugzilla to a successful creation, which contains
+/// the ID of the new item.
+#[derive(Clone, Debug, Deserialize)]
+pub struct CreatedResponse {
+    pub id: i32,
+}
+
+/// The response from Bugzilla to a successful update, which lists the changes
+/// in each updated bug. An error report doesn't match it, even with a successful status.
+// The `bugs` field is not used now, but it distinguishes the response from an error report.
+#[allow(dead_code)]
+#[derive(Clone, Debug, Deserialize)]
+pub struct UpdatedResponse {
+    pub bugs: Vec<Value>,
+}
+
+/// The response from Bugzilla to a successful change of personal tags, which lists
+/// the added and removed tags under the ID of each bug.
+// The `changes` field is not used now, but it distinguishes the response from an error report.
+#[allow(dead_code)]
+#[derive(Clone, Debug, Deserialize)]
+pub struct TagsResponse {
+    pub changes: Map<String, Value>,
+}
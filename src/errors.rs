@@ -14,6 +14,9 @@ See the License for the specific language governing permissions and
 limitations under the License.
 */
 
+use std::io;
+use std::path::PathBuf;
+
 use thiserror::Error;
 
 /// All errors that might occur in this crate.
@@ -23,6 +26,94 @@ pub enum BugzillaQueryError {
     MissingBugs(Vec<String>),
     #[error("The Bugzilla query returned no bugs.")]
     NoBugs,
+    #[error("The requested item does not exist: {id}.")]
+    NotFound { id: String },
+    #[error("The Bugzilla host is not a valid http or https URL: {0}")]
+    InvalidHost(String),
+    #[error("Cannot form a valid URL for the Bugzilla host: {0}")]
+    InvalidUrl(String),
     #[error("Error in accessing the Bugzilla REST API.")]
-    Request(#[from] reqwest::Error),
+    Request(#[source] reqwest::Error),
+    #[error("The request to the Bugzilla REST API timed out.")]
+    Timeout(#[source] reqwest::Error),
+    #[error("Bugzilla rejected the credentials.")]
+    Unauthorized,
+    #[error("The credentials don't have the permission to access the requested data.")]
+    Forbidden,
+    #[error("Bugzilla responded with the HTTP status {status}: {body}")]
+    HttpStatus { status: u16, body: String },
+    #[error("The request to the Bugzilla REST API failed after {attempts} attempts.")]
+    RetriesExhausted { attempts: u32 },
+    #[error("The {0} environment variable is set, but it doesn't contain any credentials.")]
+    MissingCredentials(String),
+    #[error("Failed to read the netrc file at {}.", path.display())]
+    Netrc {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("Bugzilla reported an error with code {code}: {message}")]
+    Api { code: i32, message: String },
+    #[error("Failed to deserialize the Bugzilla response near: {snippet}")]
+    Deserialization {
+        #[source]
+        source: serde_json::Error,
+        /// The part of the response around the position where deserialization failed.
+        snippet: String,
+    },
+    #[error("Failed to deserialize the custom fields of the bug.")]
+    CustomFields(#[source] serde_json::Error),
+    #[error("The Bugzilla instance has no field named {0}.")]
+    UnknownField(String),
+    #[error("The attachment data is missing in the Bugzilla response.")]
+    MissingAttachmentData,
+    #[error("Failed to decode the attachment data from base64.")]
+    AttachmentDecoding(#[from] base64::DecodeError),
+}
+
+impl From<reqwest::Error> for BugzillaQueryError {
+    /// Distinguish timeouts from other errors in accessing Bugzilla.
+    /// The URL of the request can contain the session token or the password,
+    /// so the error leaves it out.
+    fn from(error: reqwest::Error) -> Self {
+        let error = error.without_url();
+        if error.is_timeout() {
+            Self::Timeout(error)
+        } else {
+            Self::Request(error)
+        }
+    }
+}
+
+/// How many bytes of the response the snippet shows on each side of the failed position.
+const SNIPPET_CONTEXT: usize = 40;
+
+impl BugzillaQueryError {
+    /// Report the failed deserialization along with a snippet of the body
+    /// around the position where it failed.
+    pub(crate) fn deserialization(source: serde_json::Error, body: &[u8]) -> Self {
+        let body = String::from_utf8_lossy(body);
+        // Errors that don't come from the parser, such as custom ones, have no position.
+        let (line, column) = match source.line() {
+            0 => (body.lines().next().unwrap_or_default(), 0),
+            line => (
+                body.lines().nth(line - 1).unwrap_or_default(),
+                source.column(),
+            ),
+        };
+
+        let mut start = column.saturating_sub(SNIPPET_CONTEXT).min(line.len());
+        while !line.is_char_boundary(start) {
+            start -= 1;
+        }
+        let mut end = (column + SNIPPET_CONTEXT).min(line.len());
+        while !line.is_char_boundary(end) {
+            end += 1;
+        }
+
+        Self::Deserialization {
+            source,
+            snippet: line[start..end].to_string(),
+        }
+    }
 }
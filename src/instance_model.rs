@@ -0,0 +1,85 @@
+/*
+Copyright 2022 Marek Suchánek <msuchane@redhat.com>
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+//! This module replicates the metadata about a Bugzilla instance, such as its products and fields,
+//! as strongly typed structs. Any extra fields are captured in the `extra` hash map
+//! in the parent struct.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// The response from Bugzilla to a request for products.
+#[derive(Clone, Debug, Deserialize)]
+pub struct ProductsResponse {
+    pub products: Vec<Product>,
+}
+
+/// The representation of a product, which groups the components of a project.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Product {
+    pub id: i32,
+    pub name: String,
+    pub description: String,
+    pub is_active: bool,
+    pub components: Vec<ProductComponent>,
+    #[serde(flatten)]
+    pub extra: Value,
+}
+
+/// The representation of a component within a product.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct ProductComponent {
+    pub id: i32,
+    pub name: String,
+    pub description: String,
+    pub is_active: bool,
+    #[serde(flatten)]
+    pub extra: Value,
+}
+
+/// The response from Bugzilla to a request for field definitions.
+#[derive(Clone, Debug, Deserialize)]
+pub struct FieldsResponse {
+    pub fields: Vec<FieldDefinition>,
+}
+
+/// The definition of a bug field on this Bugzilla instance, including the legal values
+/// of fields that offer a selection, such as `bug_status`.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct FieldDefinition {
+    pub id: i32,
+    pub name: String,
+    pub display_name: String,
+    /// The numeric type of the field, such as 2 for a drop-down field.
+    #[serde(rename = "type")]
+    pub field_type: i32,
+    pub is_custom: bool,
+    /// The legal values of the field. Empty for fields that don't offer a selection.
+    #[serde(default)]
+    pub values: Vec<FieldValue>,
+    #[serde(flatten)]
+    pub extra: Value,
+}
+
+/// A single legal value of a field.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct FieldValue {
+    pub name: String,
+    pub sort_key: i32,
+    pub is_active: bool,
+    #[serde(flatten)]
+    pub extra: Value,
+}
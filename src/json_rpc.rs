@@ -0,0 +1,94 @@
+/*
+Copyright 2022 Marek Suchánek <msuchane@redhat.com>
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+//! This module builds requests to the legacy JSON-RPC API of Bugzilla at `/jsonrpc.cgi`,
+//! and unwraps the responses from its envelope.
+
+use serde::de::{self, DeserializeOwned};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+use crate::errors::BugzillaQueryError;
+use crate::search::decode;
+
+/// The response envelope of the JSON-RPC API, which contains either a result or an error.
+#[derive(Deserialize)]
+struct Envelope<T> {
+    result: Option<T>,
+    error: Option<EnvelopeError>,
+}
+
+/// The error in the JSON-RPC response envelope.
+#[derive(Deserialize)]
+struct EnvelopeError {
+    code: i32,
+    message: String,
+}
+
+/// Wrap the parameters in the JSON-RPC request structure for the method, such as `Bug.get`.
+pub(crate) fn request_body(method: &str, params: Map<String, Value>) -> Value {
+    let mut body = Map::new();
+    body.insert("method".to_string(), json!(method));
+    body.insert(
+        "params".to_string(),
+        Value::Array(vec![Value::Object(params)]),
+    );
+    body.insert("id".to_string(), json!(1));
+    Value::Object(body)
+}
+
+/// Add a parameter to the JSON-RPC parameters. If the parameter repeats,
+/// collect its values in a list, the same way as the REST API does.
+pub(crate) fn add_param(params: &mut Map<String, Value>, key: &str, value: Value) {
+    match params.get_mut(key) {
+        Some(Value::Array(values)) => values.push(value),
+        Some(previous) => *previous = Value::Array(vec![previous.take(), value]),
+        None => {
+            params.insert(key.to_string(), value);
+        }
+    }
+}
+
+/// Convert a free-form search query, such as `component=rust&product=Fedora`,
+/// to JSON-RPC parameters. Decode any percent-encoded keys and values,
+/// and `+` as a space.
+pub(crate) fn query_params(query: &str, params: &mut Map<String, Value>) {
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let key = String::from_utf8_lossy(&decode(key)).into_owned();
+        let value = String::from_utf8_lossy(&decode(value)).into_owned();
+        add_param(params, &key, Value::String(value));
+    }
+}
+
+/// Deserialize the result from the JSON-RPC response envelope,
+/// or return the error in the envelope.
+pub(crate) fn parse_envelope<T: DeserializeOwned>(body: &[u8]) -> Result<T, BugzillaQueryError> {
+    let envelope: Envelope<T> = serde_json::from_slice(body)
+        .map_err(|error| BugzillaQueryError::deserialization(error, body))?;
+
+    match (envelope.result, envelope.error) {
+        (_, Some(error)) => Err(BugzillaQueryError::Api {
+            code: error.code,
+            message: error.message,
+        }),
+        (Some(result), None) => Ok(result),
+        (None, None) => Err(BugzillaQueryError::deserialization(
+            de::Error::custom("The JSON-RPC response contains neither a result nor an error."),
+            body,
+        )),
+    }
+}
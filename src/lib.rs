@@ -26,12 +26,30 @@ limitations under the License.
 #![forbid(unsafe_code)]
 
 mod access;
+#[cfg(feature = "blocking")]
+pub mod blocking;
 mod bug_methods;
 mod bug_model;
+mod bug_source;
+#[cfg(feature = "cache")]
+mod cache;
+mod change_model;
 mod errors;
+mod instance_model;
+mod json_rpc;
+mod netrc;
+mod search;
 
-pub use access::{Auth, BzInstance, Pagination};
-pub use bug_model::{Alias, Bug, Component, Flag, OneOrMany, User, Version};
+pub use access::{Auth, BzInstance, Direction, Pagination, Transport};
+pub use bug_methods::{changes_between, latest_change_time};
+pub use bug_model::{
+    Alias, Attachment, Bug, Comment, Component, DependencyGraph, FieldChange, Flag, HistoryEntry,
+    OneOrMany, Resolution, SearchResult, SeeAlsoLink, Status, TimeTracking, User, Version,
+};
+pub use bug_source::BugSource;
+pub use change_model::{BugUpdate, NewBug};
 pub use errors::BugzillaQueryError;
+pub use instance_model::{FieldDefinition, FieldValue, Product, ProductComponent};
+pub use search::{Op, SearchBuilder};
 // Re-export JSON Value because it's an integral part of the bug model.
 pub use serde_json::Value;
@@ -0,0 +1,104 @@
+/*
+Copyright 2022 Marek Suchánek <msuchane@redhat.com>
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+//! This module parses the `.netrc` file, which stores the credentials for remote hosts.
+
+/// The login and password of a single entry in the file.
+#[derive(Default)]
+struct Entry {
+    login: Option<String>,
+    password: Option<String>,
+}
+
+impl Entry {
+    fn credentials(self) -> Option<(String, String)> {
+        self.login.zip(self.password)
+    }
+}
+
+/// The entry that the tokens currently belong to.
+#[derive(Clone, Copy)]
+enum Target {
+    Matching,
+    Default,
+    Other,
+}
+
+/// Find the login and password for the host in the contents of a `.netrc` file.
+/// If no `machine` entry matches the host, use the `default` entry, if any.
+/// Entries without both a login and a password are skipped.
+pub(crate) fn credentials(contents: &str, host: &str) -> Option<(String, String)> {
+    let mut matching: Option<Entry> = None;
+    let mut default: Option<Entry> = None;
+    let mut target = Target::Other;
+
+    let mut lines = contents.lines();
+    while let Some(line) = lines.next() {
+        let mut tokens = line.split_whitespace();
+        while let Some(token) = tokens.next() {
+            match token {
+                "machine" => {
+                    // The first matching entry takes precedence.
+                    target = if tokens.next() == Some(host) && matching.is_none() {
+                        matching = Some(Entry::default());
+                        Target::Matching
+                    } else {
+                        Target::Other
+                    };
+                }
+                "default" => {
+                    default = Some(Entry::default());
+                    target = Target::Default;
+                }
+                "login" | "password" => {
+                    let value = tokens.next().map(str::to_string);
+                    let entry = match target {
+                        Target::Matching => matching.as_mut(),
+                        Target::Default => default.as_mut(),
+                        Target::Other => None,
+                    };
+                    if let Some(entry) = entry {
+                        if token == "login" {
+                            entry.login = value;
+                        } else {
+                            entry.password = value;
+                        }
+                    }
+                }
+                "account" => {
+                    tokens.next();
+                }
+                // A macro definition continues until the next empty line.
+                "macdef" => {
+                    target = Target::Other;
+                    for line in lines.by_ref() {
+                        if line.trim().is_empty() {
+                            break;
+                        }
+                    }
+                    break;
+                }
+                // Comments continue until the end of the line.
+                _ if token.starts_with('#') => break,
+                _ => {}
+            }
+        }
+    }
+
+    matching
+        .and_then(Entry::credentials)
+        .or_else(|| default.and_then(Entry::credentials))
+}
@@ -0,0 +1,385 @@
+/*
+Copyright 2022 Marek Suchánek <msuchane@redhat.com>
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+//! This module builds Bugzilla search queries programmatically, including the advanced
+//! search conditions, which Bugzilla calls boolean charts.
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use percent_encoding::{
+    percent_decode_str, percent_encode, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC,
+};
+
+/// The characters to percent-encode in the keys and values of a query.
+/// Only the unreserved characters in RFC 3986 remain as they are.
+const QUERY_COMPONENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+/// The shortest word in a summary that the search for possible duplicates uses.
+const MIN_TERM_LENGTH: usize = 3;
+
+/// The common English words that the search for possible duplicates skips,
+/// because they appear in the summaries of most bugs.
+const STOP_WORDS: &[&str] = &[
+    "about", "after", "all", "also", "and", "any", "are", "before", "but", "can", "cannot",
+    "could", "does", "for", "from", "has", "have", "into", "its", "not", "now", "off", "out",
+    "should", "than", "that", "the", "then", "there", "these", "this", "those", "too", "using",
+    "was", "were", "when", "which", "while", "will", "with", "would", "you", "your",
+];
+
+/// Percent-encode a key or a value in a URL query, so that it can't break
+/// the `key=value&key=value` structure.
+pub(crate) fn encode(component: &str) -> String {
+    utf8_percent_encode(component, QUERY_COMPONENT).to_string()
+}
+
+/// Decode a key or a value in a query copied from Bugzilla, where `+` stands for a space
+/// and escapes such as `%2B` stand for the encoded characters.
+pub(crate) fn decode(component: &str) -> Vec<u8> {
+    percent_decode_str(&component.replace('+', " ")).collect()
+}
+
+/// Percent-encode a key or a value that might already be partially encoded.
+/// Valid escapes such as `%20` keep their meaning, as does `+` for a space.
+/// All other reserved characters, including a `%` that doesn't start an escape,
+/// become encoded.
+fn reencode(component: &str) -> String {
+    percent_encode(&decode(component), QUERY_COMPONENT).to_string()
+}
+
+/// Percent-encode the keys and values in a free-form query, such as
+/// `summary=foo bar&component=C/C%2B%2B`, without breaking its `key=value&key=value` structure.
+pub(crate) fn encode_query(query: &str) -> String {
+    query
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((key, value)) => format!("{}={}", reencode(key), reencode(value)),
+            None => reencode(pair),
+        })
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// Format the timestamp for Bugzilla in the ISO 8601 format, such as `2022-05-01T12:30:00Z`.
+pub(crate) fn format_timestamp(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Format the parameters as a URL query, such as `product=Fedora&component=rust`,
+/// with the keys and values percent-encoded.
+pub(crate) fn encode_params(params: &[(String, String)]) -> String {
+    params
+        .iter()
+        .map(|(key, value)| format!("{}={}", encode(key), encode(value)))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// The operator that compares a field with a value in an advanced search condition.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Op {
+    Equals,
+    NotEquals,
+    AnyExact,
+    Substring,
+    CaseSubstring,
+    NotSubstring,
+    AnyWordsSubstring,
+    AllWordsSubstring,
+    NoWordsSubstring,
+    Regexp,
+    NotRegexp,
+    LessThan,
+    LessThanEq,
+    GreaterThan,
+    GreaterThanEq,
+    AnyWords,
+    AllWords,
+    NoWords,
+    ChangedBefore,
+    ChangedAfter,
+    ChangedFrom,
+    ChangedTo,
+    ChangedBy,
+    Matches,
+    IsEmpty,
+    IsNotEmpty,
+}
+
+impl Op {
+    /// Returns the name of the operator in the Bugzilla query, such as `notequals`.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Equals => "equals",
+            Self::NotEquals => "notequals",
+            Self::AnyExact => "anyexact",
+            Self::Substring => "substring",
+            Self::CaseSubstring => "casesubstring",
+            Self::NotSubstring => "notsubstring",
+            Self::AnyWordsSubstring => "anywordssubstr",
+            Self::AllWordsSubstring => "allwordssubstr",
+            Self::NoWordsSubstring => "nowordssubstr",
+            Self::Regexp => "regexp",
+            Self::NotRegexp => "notregexp",
+            Self::LessThan => "lessthan",
+            Self::LessThanEq => "lessthaneq",
+            Self::GreaterThan => "greaterthan",
+            Self::GreaterThanEq => "greaterthaneq",
+            Self::AnyWords => "anywords",
+            Self::AllWords => "allwords",
+            Self::NoWords => "nowords",
+            Self::ChangedBefore => "changedbefore",
+            Self::ChangedAfter => "changedafter",
+            Self::ChangedFrom => "changedfrom",
+            Self::ChangedTo => "changedto",
+            Self::ChangedBy => "changedby",
+            Self::Matches => "matches",
+            Self::IsEmpty => "isempty",
+            Self::IsNotEmpty => "isnotempty",
+        }
+    }
+}
+
+/// A single advanced search condition, such as `component equals rust`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Condition {
+    field: String,
+    op: Op,
+    value: String,
+}
+
+/// Builds a Bugzilla search query from simple parameters, such as `product=Fedora`,
+/// and from advanced search conditions, such as `component equals rust`.
+///
+/// Start each condition with `field`, then set its operator with `op` and its value
+/// with `value`. A condition without an operator uses `Op::Equals`.
+/// A bug matches the search only if it matches all parameters and conditions.
+///
+/// ```
+/// use bugzilla_query::{Op, SearchBuilder};
+///
+/// let search = SearchBuilder::new()
+///     .field("component").op(Op::Equals).value("rust")
+///     .and()
+///     .field("status").op(Op::NotEquals).value("CLOSED");
+///
+/// assert_eq!(
+///     search.to_query(),
+///     "f1=component&o1=equals&v1=rust&f2=status&o2=notequals&v2=CLOSED"
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SearchBuilder {
+    params: Vec<(String, String)>,
+    conditions: Vec<Condition>,
+}
+
+impl SearchBuilder {
+    /// Create an empty search, which matches all bugs.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a search for bugs whose summary contains any of the words
+    /// in the specified summary, optionally in a single product.
+    ///
+    /// The words are the parts of the summary between spaces, without the surrounding
+    /// punctuation, such as `crash` and `startup` in `Crash on startup.`
+    /// The search skips common words and words shorter than three characters,
+    /// which appear in nearly every summary. If no words remain, returns `None`,
+    /// because the search would match all bugs.
+    #[must_use]
+    pub fn possible_duplicates(summary: &str, product: Option<&str>) -> Option<Self> {
+        let terms: Vec<&str> = summary
+            .split_whitespace()
+            .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()))
+            .filter(|word| word.chars().count() >= MIN_TERM_LENGTH)
+            .filter(|word| {
+                !STOP_WORDS
+                    .iter()
+                    .any(|stop_word| stop_word.eq_ignore_ascii_case(word))
+            })
+            .collect();
+
+        if terms.is_empty() {
+            return None;
+        }
+
+        let search = match product {
+            Some(product) => Self::new().param("product", product),
+            None => Self::new(),
+        };
+
+        let search = search
+            .field("short_desc")
+            .op(Op::AnyWords)
+            .value(&terms.join(" "));
+        Some(search)
+    }
+
+    /// Create a search that runs a saved search by its name.
+    /// A search that another user shared requires the ID of that user as `sharer_id`.
+    #[must_use]
+    pub fn saved_search(name: &str, sharer_id: Option<&str>) -> Self {
+        let search = Self::new().param("savedsearch", name);
+
+        match sharer_id {
+            Some(sharer_id) => search.param("sharer_id", sharer_id),
+            None => search,
+        }
+    }
+
+    /// Add a simple parameter to the search, such as `product=Fedora`.
+    #[must_use]
+    pub fn param(mut self, key: &str, value: &str) -> Self {
+        self.params.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Limit the search to bugs with the keywords. If `match_all` is true,
+    /// the bugs must have all of the keywords. Otherwise, any of the keywords is enough.
+    #[must_use]
+    pub fn keywords(self, keywords: &[&str], match_all: bool) -> Self {
+        let keywords_type = if match_all { "allwords" } else { "anywords" };
+
+        self.param("keywords", &keywords.join(" "))
+            .param("keywords_type", keywords_type)
+    }
+
+    /// Limit the search to bugs assigned to the user, identified by their email.
+    #[must_use]
+    pub fn assigned_to(self, email: &str) -> Self {
+        self.param("assigned_to", email)
+    }
+
+    /// Limit the search to bugs reported by the user, identified by their email.
+    #[must_use]
+    pub fn reporter(self, email: &str) -> Self {
+        self.param("creator", email)
+    }
+
+    /// Start a new advanced search condition on the field.
+    #[must_use]
+    pub fn field(mut self, field: &str) -> Self {
+        self.conditions.push(Condition {
+            field: field.to_string(),
+            op: Op::Equals,
+            value: String::new(),
+        });
+        self
+    }
+
+    /// Set the operator of the current condition.
+    ///
+    /// Without a current condition, which you start with `field`, this does nothing.
+    #[must_use]
+    pub fn op(mut self, op: Op) -> Self {
+        if let Some(condition) = self.conditions.last_mut() {
+            condition.op = op;
+        }
+        self
+    }
+
+    /// Set the value of the current condition.
+    ///
+    /// Without a current condition, which you start with `field`, this does nothing.
+    #[must_use]
+    pub fn value(mut self, value: &str) -> Self {
+        if let Some(condition) = self.conditions.last_mut() {
+            condition.value = value.to_string();
+        }
+        self
+    }
+
+    /// Limit the search to bugs created after `after` and before `before`.
+    /// Without a bound, the search doesn't limit that end of the range.
+    #[must_use]
+    pub fn created_between(
+        self,
+        after: Option<DateTime<Utc>>,
+        before: Option<DateTime<Utc>>,
+    ) -> Self {
+        // Advanced search conditions use the internal name of the creation time field.
+        self.time_range("creation_ts", after, before)
+    }
+
+    /// Limit the search to bugs that last changed after `after` and before `before`.
+    /// Without a bound, the search doesn't limit that end of the range.
+    #[must_use]
+    pub fn changed_between(
+        self,
+        after: Option<DateTime<Utc>>,
+        before: Option<DateTime<Utc>>,
+    ) -> Self {
+        // Advanced search conditions use the internal name of the last change time field.
+        self.time_range("delta_ts", after, before)
+    }
+
+    /// Add a condition for each bound of the time range on the field.
+    fn time_range(
+        mut self,
+        field: &str,
+        after: Option<DateTime<Utc>>,
+        before: Option<DateTime<Utc>>,
+    ) -> Self {
+        if let Some(after) = after {
+            self = self
+                .field(field)
+                .op(Op::GreaterThan)
+                .value(&format_timestamp(after));
+        }
+        if let Some(before) = before {
+            self = self
+                .field(field)
+                .op(Op::LessThan)
+                .value(&format_timestamp(before));
+        }
+        self
+    }
+
+    /// Separate the current condition from the next one. All conditions must match,
+    /// so this only makes the chain of conditions easier to read.
+    #[must_use]
+    pub fn and(self) -> Self {
+        self
+    }
+
+    /// List the parameters of the search as unencoded key and value pairs,
+    /// with the advanced conditions numbered in the order that you added them.
+    #[must_use]
+    pub(crate) fn to_params(&self) -> Vec<(String, String)> {
+        let mut params = self.params.clone();
+
+        for (index, condition) in self.conditions.iter().enumerate() {
+            let number = index + 1;
+            params.push((format!("f{number}"), condition.field.clone()));
+            params.push((format!("o{number}"), condition.op.as_str().to_string()));
+            params.push((format!("v{number}"), condition.value.clone()));
+        }
+
+        params
+    }
+
+    /// Format the search as a URL query, with the keys and values percent-encoded.
+    #[must_use]
+    pub fn to_query(&self) -> String {
+        encode_params(&self.to_params())
+    }
+}
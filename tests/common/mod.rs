@@ -0,0 +1,230 @@
+//! A minimal HTTP server that replies with canned responses, so that tests can run
+//! without access to a real Bugzilla instance.
+
+// Not every test file uses every helper.
+#![allow(dead_code)]
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// A canned response that the mock server sends back.
+#[derive(Clone, Debug)]
+pub struct MockResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+    /// Wait this long before sending the response.
+    pub delay: Duration,
+}
+
+impl MockResponse {
+    /// A response with the specified status and a JSON body.
+    pub fn json(status: u16, body: &str) -> Self {
+        Self {
+            status,
+            headers: vec![("Content-Type".to_string(), "application/json".to_string())],
+            body: body.to_string(),
+            delay: Duration::ZERO,
+        }
+    }
+
+    /// A successful response with a JSON body.
+    pub fn ok(body: &str) -> Self {
+        Self::json(200, body)
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+}
+
+/// A request that the mock server received.
+#[derive(Clone, Debug)]
+pub struct MockRequest {
+    pub method: String,
+    /// The path of the request, including the query string.
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl MockRequest {
+    /// The value of the header, matched case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// A function that decides how the mock server replies to a request.
+type Responder = dyn Fn(&MockRequest) -> MockResponse + Send + Sync;
+
+/// The state of the server, shared across connections.
+struct State {
+    requests: Mutex<Vec<MockRequest>>,
+    responder: Box<Responder>,
+    in_flight: AtomicUsize,
+    max_in_flight: AtomicUsize,
+}
+
+/// A server on a random local port that replies with canned responses.
+pub struct MockServer {
+    address: String,
+    state: Arc<State>,
+}
+
+impl MockServer {
+    /// Start a server that replies with the responses in order.
+    /// After it uses up the responses, it keeps repeating the last one.
+    pub async fn start(responses: Vec<MockResponse>) -> Self {
+        let responses = Mutex::new(VecDeque::from(responses));
+        Self::start_with(move |_| {
+            let mut responses = responses.lock().unwrap();
+            if responses.len() > 1 {
+                responses.pop_front().unwrap()
+            } else {
+                responses.front().cloned().unwrap()
+            }
+        })
+        .await
+    }
+
+    /// Start a server that replies with the response that the function
+    /// returns for each request.
+    pub async fn start_with(
+        responder: impl Fn(&MockRequest) -> MockResponse + Send + Sync + 'static,
+    ) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = format!("http://{}", listener.local_addr().unwrap());
+        let state = Arc::new(State {
+            requests: Mutex::new(Vec::new()),
+            responder: Box::new(responder),
+            in_flight: AtomicUsize::new(0),
+            max_in_flight: AtomicUsize::new(0),
+        });
+
+        let shared = Arc::clone(&state);
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                let state = Arc::clone(&shared);
+                tokio::spawn(async move {
+                    serve(stream, &state).await;
+                });
+            }
+        });
+
+        Self { address, state }
+    }
+
+    /// The URL of the server, usable as the Bugzilla host.
+    pub fn url(&self) -> String {
+        self.address.clone()
+    }
+
+    /// All requests that the server has received so far.
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.state.requests.lock().unwrap().clone()
+    }
+
+    /// The highest number of requests that the server was handling at the same time.
+    pub fn max_in_flight(&self) -> usize {
+        self.state.max_in_flight.load(Ordering::SeqCst)
+    }
+}
+
+/// Read a single request from the connection, record it, and reply.
+async fn serve(mut stream: TcpStream, state: &State) {
+    let request = match read_request(&mut stream).await {
+        Some(request) => request,
+        None => return,
+    };
+
+    let in_flight = state.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+    state.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+
+    let response = (state.responder)(&request);
+    state.requests.lock().unwrap().push(request);
+
+    tokio::time::sleep(response.delay).await;
+
+    let mut head = format!(
+        "HTTP/1.1 {} Mock\r\nContent-Length: {}\r\nConnection: close\r\n",
+        response.status,
+        response.body.len()
+    );
+    for (name, value) in &response.headers {
+        head.push_str(&format!("{name}: {value}\r\n"));
+    }
+    head.push_str("\r\n");
+
+    // The client might have given up on the request already, such as after a timeout.
+    let _ = stream.write_all(head.as_bytes()).await;
+    let _ = stream.write_all(response.body.as_bytes()).await;
+    let _ = stream.shutdown().await;
+
+    state.in_flight.fetch_sub(1, Ordering::SeqCst);
+}
+
+async fn read_request(stream: &mut TcpStream) -> Option<MockRequest> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0; 4096];
+
+    // Read until the end of the headers.
+    let head_end = loop {
+        if let Some(position) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+            break position;
+        }
+        let read = stream.read(&mut chunk).await.ok()?;
+        if read == 0 {
+            return None;
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    };
+
+    let head = String::from_utf8_lossy(&buffer[..head_end]).to_string();
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next()?.split(' ');
+    let method = request_line.next()?.to_string();
+    let path = request_line.next()?.to_string();
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|line| line.split_once(": "))
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+
+    let content_length: usize = headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.parse().ok())
+        .unwrap_or(0);
+
+    // Read the rest of the body.
+    let mut body = buffer[head_end + 4..].to_vec();
+    while body.len() < content_length {
+        let read = stream.read(&mut chunk).await.ok()?;
+        if read == 0 {
+            break;
+        }
+        body.extend_from_slice(&chunk[..read]);
+    }
+
+    Some(MockRequest {
+        method,
+        path,
+        headers,
+        body: String::from_utf8_lossy(&body).to_string(),
+    })
+}
@@ -0,0 +1,301 @@
+//! Tests of the configuration of `BzInstance` and its options that don't send any requests.
+
+use std::env;
+
+use bugzilla_query::*;
+
+/// Check that the credentials load from the environment variables in the order of preference.
+///
+/// A single test covers all cases because the environment is shared across threads.
+#[test]
+fn auth_from_env() {
+    let vars = ["BUGZILLA_API_KEY", "BUGZILLA_USER", "BUGZILLA_PASSWORD"];
+    for var in vars {
+        env::remove_var(var);
+    }
+
+    assert!(matches!(Auth::from_env(), Ok(Auth::Anonymous)));
+
+    // An incomplete pair of basic credentials isn't enough.
+    env::set_var("BUGZILLA_USER", "user");
+    assert!(matches!(Auth::from_env(), Ok(Auth::Anonymous)));
+
+    env::set_var("BUGZILLA_PASSWORD", "password");
+    assert!(matches!(
+        Auth::from_env(),
+        Ok(Auth::Basic { user, password }) if user == "user" && password == "password"
+    ));
+
+    // The API key takes precedence.
+    env::set_var("BUGZILLA_API_KEY", "key");
+    assert!(matches!(Auth::from_env(), Ok(Auth::ApiKey(key)) if key == "key"));
+
+    env::set_var("BUGZILLA_API_KEY", "");
+    assert!(matches!(
+        Auth::from_env(),
+        Err(BugzillaQueryError::MissingCredentials(var)) if var == "BUGZILLA_API_KEY"
+    ));
+
+    for var in vars {
+        env::remove_var(var);
+    }
+}
+
+/// Check that extra fields add to the included fields without duplicates,
+/// while `include_fields` replaces them.
+#[test]
+fn include_extra_fields() {
+    let instance = BzInstance::at("https://bugzilla.redhat.com".to_string())
+        .unwrap()
+        .include_extra_fields(vec!["flags".to_string(), "_default".to_string()]);
+    assert_eq!(instance.included_fields, vec!["_default", "flags"]);
+
+    let instance = instance
+        .include_fields(vec!["id".to_string()])
+        .include_extra_fields(vec!["summary".to_string(), "summary".to_string()]);
+    assert_eq!(instance.included_fields, vec!["id", "summary"]);
+}
+
+/// Check the URLs of requests, with and without pagination,
+/// and that the token in the URL is redacted.
+#[test]
+fn debug_urls() {
+    let instance = BzInstance::at("https://bugzilla.redhat.com".to_string()).unwrap();
+    assert_eq!(
+        instance.debug_url_for_search("component=rust&summary=foo bar"),
+        "https://bugzilla.redhat.com/rest/bug?component=rust&summary=foo%20bar&include_fields=_default"
+    );
+    assert_eq!(
+        instance.debug_url_for_ids(&["1906883", "1906887"]),
+        "https://bugzilla.redhat.com/rest/bug?id=1906883,1906887&include_fields=_default"
+    );
+
+    let instance = instance
+        .paginate(Pagination::Limit(20))
+        .authenticate(Auth::Token("secret".to_string()));
+    let url = instance.debug_url_for_search("component=rust");
+    assert_eq!(
+        url,
+        "https://bugzilla.redhat.com/rest/bug?component=rust&include_fields=_default&limit=20&token=REDACTED"
+    );
+    assert!(!url.contains("secret"));
+}
+
+/// Check that the debugging output shows the JSON-RPC request with the transport,
+/// with the credentials in the body redacted.
+#[test]
+fn debug_urls_json_rpc() {
+    let instance = BzInstance::at("https://bugzilla.redhat.com".to_string())
+        .unwrap()
+        .transport(Transport::JsonRpc)
+        .authenticate(Auth::ApiKey("secret".to_string()));
+
+    let request = instance.debug_url_for_ids(&["1906883"]);
+    assert!(request.starts_with("POST https://bugzilla.redhat.com/jsonrpc.cgi {"));
+    assert!(request.contains(r#""method":"Bug.get""#));
+    assert!(request.contains(r#""ids":["1906883"]"#));
+    assert!(request.contains(r#""Bugzilla_api_key":"REDACTED""#));
+    assert!(!request.contains("secret"));
+
+    let request = instance.debug_url_for_search("component=rust");
+    assert!(request.contains(r#""method":"Bug.search""#));
+    assert!(request.contains(r#""component":"rust""#));
+}
+
+/// Check that the host loses its trailing slash, and that a host without
+/// an `http` or `https` scheme is rejected.
+#[test]
+fn validate_host() {
+    let instance = BzInstance::at("https://bugzilla.redhat.com".to_string()).unwrap();
+    assert_eq!(instance.host, "https://bugzilla.redhat.com");
+
+    let instance = BzInstance::at("https://bugzilla.redhat.com/".to_string()).unwrap();
+    assert_eq!(instance.host, "https://bugzilla.redhat.com");
+    assert!(instance
+        .debug_url_for_ids(&["1"])
+        .starts_with("https://bugzilla.redhat.com/rest/bug?"));
+
+    for host in ["bugzilla.redhat.com", "ftp://bugzilla.redhat.com", ""] {
+        let result = BzInstance::at(host.to_string());
+        assert!(matches!(result, Err(BugzillaQueryError::InvalidHost(invalid)) if invalid == host));
+    }
+}
+
+/// Check that requesting all fields replaces the included fields with `_all`.
+#[test]
+fn include_all_fields() {
+    let instance = BzInstance::at("https://bugzilla.redhat.com".to_string())
+        .unwrap()
+        .include_extra_fields(vec!["flags".to_string()])
+        .include_all_fields();
+
+    assert_eq!(instance.included_fields, vec!["_all"]);
+    assert!(instance
+        .debug_url_for_ids(&["1906883"])
+        .ends_with("?id=1906883&include_fields=_all"));
+}
+
+/// Check that the debug output of the authentication contains no secrets.
+#[test]
+fn redacted_auth_debug() {
+    let api_key = format!("{:?}", Auth::ApiKey("secret-key".to_string()));
+    assert_eq!(api_key, r#"ApiKey("***")"#);
+
+    let basic = format!(
+        "{:?}",
+        Auth::Basic {
+            user: "user".to_string(),
+            password: "secret-password".to_string(),
+        }
+    );
+    assert_eq!(basic, r#"Basic { user: "user", password: "***" }"#);
+
+    let token = format!("{:#?}", Auth::Token("secret-token".to_string()));
+    assert!(!token.contains("secret"));
+}
+
+/// Check that the API version adds a path segment after the REST path.
+#[test]
+fn api_version() {
+    let instance = BzInstance::at("https://bugzilla.redhat.com".to_string()).unwrap();
+    assert_eq!(
+        instance.debug_url_for_ids(&["1906883"]),
+        "https://bugzilla.redhat.com/rest/bug?id=1906883&include_fields=_default"
+    );
+
+    let instance = instance.api_version(Some(1));
+    assert_eq!(
+        instance.debug_url_for_ids(&["1906883"]),
+        "https://bugzilla.redhat.com/rest/v1/bug?id=1906883&include_fields=_default"
+    );
+}
+
+/// Check that accepting invalid certificates is disabled by default and that the builder sets it.
+#[test]
+fn accept_invalid_certs() {
+    let instance = BzInstance::at("https://bugzilla.internal.example.com".to_string()).unwrap();
+    assert!(!instance.accepts_invalid_certs());
+
+    let instance = instance.danger_accept_invalid_certs(true);
+    assert!(instance.accepts_invalid_certs());
+
+    let instance = instance.danger_accept_invalid_certs(false);
+    assert!(!instance.accepts_invalid_certs());
+}
+
+/// Check the effective limit of each pagination variant.
+#[test]
+fn effective_limit() {
+    assert_eq!(Pagination::Default.effective_limit(), None);
+    assert_eq!(Pagination::Limit(50).effective_limit(), Some(50));
+    assert_eq!(Pagination::Unlimited.effective_limit(), Some(0));
+    assert_eq!(
+        Pagination::Page {
+            limit: 20,
+            offset: 40
+        }
+        .effective_limit(),
+        Some(20)
+    );
+
+    let instance = BzInstance::at("https://bugzilla.redhat.com".to_string())
+        .unwrap()
+        .paginate(Pagination::Limit(50));
+    assert_eq!(instance.pagination.effective_limit(), Some(50));
+}
+
+/// Check that the credentials load from the netrc file for the matching host.
+///
+/// A single test covers all cases because the environment is shared across threads.
+#[test]
+fn auth_from_netrc() {
+    let fixture = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/netrc");
+    env::set_var("NETRC", fixture);
+
+    for host in ["bugzilla.redhat.com", "https://bugzilla.redhat.com"] {
+        assert!(matches!(
+            Auth::from_netrc(host),
+            Ok(Auth::Basic { user, password })
+                if user == "user@example.com" && password == "bugzilla-password"
+        ));
+    }
+
+    // The entry in the macro definition doesn't count, and neither does an incomplete entry,
+    // so these hosts use the default entry.
+    for host in ["bugzilla.mozilla.org", "bugzilla.kernel.org"] {
+        assert!(matches!(
+            Auth::from_netrc(host),
+            Ok(Auth::Basic { user, password }) if user == "anonymous" && password == "guest"
+        ));
+    }
+
+    // Without the default entry, an unknown host is anonymous.
+    let without_default = env::temp_dir().join("bugzilla_query_netrc");
+    std::fs::write(
+        &without_default,
+        "machine github.com login octocat password token\n",
+    )
+    .unwrap();
+    env::set_var("NETRC", &without_default);
+    assert!(matches!(
+        Auth::from_netrc("bugzilla.redhat.com"),
+        Ok(Auth::Anonymous)
+    ));
+    std::fs::remove_file(&without_default).unwrap();
+
+    env::set_var("NETRC", "/nonexistent/netrc");
+    assert!(matches!(
+        Auth::from_netrc("bugzilla.redhat.com"),
+        Err(BugzillaQueryError::Netrc { .. })
+    ));
+
+    env::remove_var("NETRC");
+}
+
+/// Check that the helper fields add to the default fields, and only once.
+#[test]
+fn with_helper_fields() {
+    let instance = BzInstance::at("https://bugzilla.redhat.com".to_string())
+        .unwrap()
+        .with_flags()
+        .with_tags()
+        .with_flags();
+    assert_eq!(instance.included_fields, vec!["_default", "flags", "tags"]);
+}
+
+/// Check that the default scope adds to the searches, unless the search sets the field itself.
+#[test]
+fn default_scope() {
+    let instance = BzInstance::at("https://bugzilla.redhat.com".to_string())
+        .unwrap()
+        .default_product("Fedora".to_string())
+        .default_component("rust".to_string());
+
+    assert_eq!(
+        instance.debug_url_for_search("version=36"),
+        "https://bugzilla.redhat.com/rest/bug?version=36&product=Fedora&component=rust&include_fields=_default"
+    );
+    assert_eq!(
+        instance.debug_url_for_search("product=Fedora EPEL&version=9"),
+        "https://bugzilla.redhat.com/rest/bug?product=Fedora%20EPEL&version=9&component=rust&include_fields=_default"
+    );
+    // Accessing bugs by their IDs ignores the scope.
+    assert_eq!(
+        instance.debug_url_for_ids(&["1906883"]),
+        "https://bugzilla.redhat.com/rest/bug?id=1906883&include_fields=_default"
+    );
+}
+
+/// Check that an error from the HTTP client converts to the error of this crate with `?`.
+#[test]
+fn convert_reqwest_error() {
+    fn build_request() -> Result<reqwest::Request, BugzillaQueryError> {
+        let request = reqwest::Client::new().get("not a URL").build()?;
+        Ok(request)
+    }
+
+    assert!(matches!(
+        build_request(),
+        Err(BugzillaQueryError::Request(_))
+    ));
+}
@@ -1,5 +1,4 @@
 use serde_json::Value;
-use tokio;
 
 use bugzilla_query::*;
 
@@ -32,7 +31,10 @@ async fn access_missing_bug() {
     let instance = rh_bugzilla();
     let bug = instance.bug("111111111111111111");
 
-    assert!(matches!(bug.await.unwrap_err(), BugzillaQueryError::NoBugs));
+    assert!(matches!(
+        bug.await.unwrap_err(),
+        BugzillaQueryError::NotFound { .. }
+    ));
 }
 
 /// Check that the bug fields contain the expected values.
@@ -44,19 +46,19 @@ async fn check_standard_fields() {
 
     assert_eq!(bug.id, 1906887);
     assert_eq!(
-        bug.summary,
-        "Test the CoRN release notes generator (populated)"
+        bug.summary.as_deref(),
+        Some("Test the CoRN release notes generator (populated)")
     );
-    assert_eq!(bug.status, "CLOSED");
-    assert_eq!(bug.resolution, "CURRENTRELEASE");
-    assert_eq!(bug.is_open, false);
+    assert_eq!(bug.status, Some(Status::Closed));
+    assert_eq!(bug.resolution_raw(), Some("CURRENTRELEASE"));
+    assert_eq!(bug.is_open, Some(false));
     assert_eq!(
         bug.component,
         Component::Many(vec!["Documentation".to_string()])
     );
-    assert_eq!(bug.priority, "medium");
-    assert_eq!(bug.assigned_to, "Marek Suchánek");
-    assert_eq!(bug.assigned_to_detail.email, "msuchane");
+    assert_eq!(bug.priority.as_deref(), Some("medium"));
+    assert_eq!(bug.assigned_to.as_deref(), Some("Marek Suchánek"));
+    assert_eq!(bug.assigned_to_detail.unwrap().email, "msuchane");
     assert_eq!(bug.docs_contact, Some("Marek Suchánek".to_string()));
     assert_eq!(bug.docs_contact_detail.unwrap().email, "msuchane");
 }
@@ -69,7 +71,7 @@ async fn check_time() {
     let bug = instance.bug("1906887").await.unwrap();
 
     let date_created = chrono::NaiveDate::from_ymd_opt(2020, 12, 11).unwrap();
-    assert_eq!(bug.creation_time.date_naive(), date_created);
+    assert_eq!(bug.creation_time.unwrap().date_naive(), date_created);
 }
 
 /// Check that the bug fields contain the expected values.
@@ -122,6 +124,178 @@ async fn search_for_bugs() {
     assert!(bugs.len() > 1);
 }
 
+/// Check that the full search result reports the number of all matching bugs.
+#[tokio::test]
+async fn search_full() {
+    let instance = rh_bugzilla().paginate(Pagination::Limit(2));
+    let query = "component=rust&product=Fedora";
+    let result = instance.search_full(query).await.unwrap();
+    assert_eq!(result.bugs.len(), 2);
+    assert!(result.total_matches.unwrap() > 2);
+}
+
+/// Check that counting bugs agrees with the number of bugs that a search returns.
+#[tokio::test]
+async fn count_bugs() {
+    let instance = rh_bugzilla().paginate(Pagination::Unlimited);
+    let query = "component=rust&product=Fedora&version=36";
+    let count = instance.count(query).await.unwrap();
+    let bugs = instance.search(query).await.unwrap();
+    assert_eq!(count as usize, bugs.len());
+}
+
+/// Try resolving a user account. The user endpoint requires authentication,
+/// so the test only runs with an API key in the `BUGZILLA_API_KEY` variable.
+#[tokio::test]
+async fn look_up_user() {
+    if std::env::var("BUGZILLA_API_KEY").is_err() {
+        return;
+    }
+    let instance = rh_bugzilla().authenticate(Auth::from_env().unwrap());
+    let users = instance.users(&["msuchane@redhat.com"]).await.unwrap();
+    assert_eq!(users[0].real_name, "Marek Suchánek");
+}
+
+/// Run a saved search of the authenticated user. The test only runs with an API key
+/// in the `BUGZILLA_API_KEY` variable and the name of a saved search that matches some bugs
+/// in the `BUGZILLA_SAVED_SEARCH` variable.
+#[tokio::test]
+async fn run_saved_search() {
+    let name = match std::env::var("BUGZILLA_SAVED_SEARCH") {
+        Ok(name) => name,
+        Err(_) => return,
+    };
+    let instance = rh_bugzilla().authenticate(Auth::from_env().unwrap());
+    let bugs = instance.saved_search(&name, None).await.unwrap();
+    assert!(!bugs.is_empty());
+}
+
+/// Add and remove a personal tag, and check the tags of the bug after each change.
+/// Changes require authentication, so the test only runs with an API key
+/// in the `BUGZILLA_API_KEY` variable.
+#[tokio::test]
+async fn add_and_remove_tag() {
+    if std::env::var("BUGZILLA_API_KEY").is_err() {
+        return;
+    }
+    let instance = rh_bugzilla()
+        .authenticate(Auth::from_env().unwrap())
+        .include_fields(vec!["_default".to_string(), "tags".to_string()]);
+    let tag = "bugzilla_query_test";
+
+    instance.add_tags("1906883", &[tag]).await.unwrap();
+    let bug = instance.bug("1906883").await.unwrap();
+    assert!(bug.tags.unwrap().iter().any(|t| t == tag));
+
+    instance.remove_tags("1906883", &[tag]).await.unwrap();
+    let bug = instance.bug("1906883").await.unwrap();
+    assert!(!bug.tags.unwrap_or_default().iter().any(|t| t == tag));
+}
+
+/// Create a bug on a sandbox instance, which is safe for testing changes.
+/// The test only runs with the URL of the sandbox in the `BUGZILLA_SANDBOX` variable
+/// and an API key in the `BUGZILLA_API_KEY` variable.
+#[tokio::test]
+async fn create_sandbox_bug() {
+    let host = match std::env::var("BUGZILLA_SANDBOX") {
+        Ok(host) => host,
+        Err(_) => return,
+    };
+    let instance = BzInstance::at(host)
+        .unwrap()
+        .authenticate(Auth::from_env().unwrap());
+    let new = NewBug {
+        product: "TestProduct".to_string(),
+        component: "TestComponent".to_string(),
+        summary: "Test bug created by bugzilla_query".to_string(),
+        version: "unspecified".to_string(),
+        op_sys: "All".to_string(),
+        platform: "All".to_string(),
+        severity: "unspecified".to_string(),
+        ..NewBug::default()
+    };
+
+    let id = instance.create_bug(new).await.unwrap();
+    let bug = instance.bug(&id.to_string()).await.unwrap();
+    assert_eq!(
+        bug.summary.as_deref(),
+        Some("Test bug created by bugzilla_query")
+    );
+}
+
+/// Change the status of a bug on a sandbox instance and read it back.
+/// The test only runs with the URL of the sandbox in the `BUGZILLA_SANDBOX` variable,
+/// a bug ID in the `BUGZILLA_SANDBOX_BUG` variable,
+/// and an API key in the `BUGZILLA_API_KEY` variable.
+#[tokio::test]
+async fn update_sandbox_bug() {
+    let (host, id) = match (
+        std::env::var("BUGZILLA_SANDBOX"),
+        std::env::var("BUGZILLA_SANDBOX_BUG"),
+    ) {
+        (Ok(host), Ok(id)) => (host, id),
+        _ => return,
+    };
+    let instance = BzInstance::at(host)
+        .unwrap()
+        .authenticate(Auth::from_env().unwrap());
+
+    let original = instance.bug(&id).await.unwrap().status.unwrap();
+    let flipped = if original == Status::Assigned {
+        "NEW"
+    } else {
+        "ASSIGNED"
+    };
+
+    let update = BugUpdate {
+        status: Some(flipped.to_string()),
+        ..BugUpdate::default()
+    };
+    instance.update_bug(&id, update).await.unwrap();
+    let bug = instance.bug(&id).await.unwrap();
+    assert_eq!(bug.status_raw(), Some(flipped));
+
+    let restore = BugUpdate {
+        status: Some(original.as_str().to_string()),
+        ..BugUpdate::default()
+    };
+    instance.update_bug(&id, restore).await.unwrap();
+}
+
+/// Post a comment to a bug on a sandbox instance and find it in the comments.
+/// The test only runs with the URL of the sandbox in the `BUGZILLA_SANDBOX` variable,
+/// a bug ID in the `BUGZILLA_SANDBOX_BUG` variable,
+/// and an API key in the `BUGZILLA_API_KEY` variable.
+#[tokio::test]
+async fn comment_on_sandbox_bug() {
+    let (host, id) = match (
+        std::env::var("BUGZILLA_SANDBOX"),
+        std::env::var("BUGZILLA_SANDBOX_BUG"),
+    ) {
+        (Ok(host), Ok(id)) => (host, id),
+        _ => return,
+    };
+    let instance = BzInstance::at(host)
+        .unwrap()
+        .authenticate(Auth::from_env().unwrap());
+
+    let comment_id = instance
+        .add_comment(&id, "Test comment posted by bugzilla_query", false)
+        .await
+        .unwrap();
+    let comments = instance.comments(&id).await.unwrap();
+    assert!(comments.iter().any(|comment| comment.id == comment_id));
+}
+
+/// Try accessing bugs that match a Bugzilla quicksearch.
+#[tokio::test]
+async fn quicksearch_for_bugs() {
+    let instance = rh_bugzilla();
+    let query = "ALL product:Fedora component:rust";
+    let bugs = instance.quicksearch(query).await.unwrap();
+    assert!(bugs.len() > 1);
+}
+
 /// Make sure that no IDs on the input result in no bugs, without errors.
 #[tokio::test]
 async fn check_no_bugs() {
@@ -142,6 +316,15 @@ async fn check_mozilla_bug() {
     let _bug = instance.bug(id).await.unwrap();
 }
 
+/// Try accessing a bug by its alias on the Mozilla instance of Bugzilla.
+#[tokio::test]
+async fn access_bug_by_alias() {
+    let instance = moz_bugzilla();
+    let bug = instance.bug_by_alias("stylo").await.unwrap();
+
+    assert_eq!(bug.id, 1243581);
+}
+
 /// Check that the alias field works correctly across different variants and configuration.
 #[tokio::test]
 async fn check_aliases() {
@@ -166,3 +349,131 @@ async fn check_aliases() {
     assert_eq!(bug.alias, OneOrMany::Many(empty.clone()));
     assert_eq!(bug.alias.into_vec(), empty.clone());
 }
+
+/// Check that a bug with only a subset of fields included deserializes,
+/// and that the other fields are empty.
+#[tokio::test]
+async fn check_included_fields() {
+    let instance = rh_bugzilla().include_fields(vec!["id".to_string(), "summary".to_string()]);
+    let bug = instance.bug("1906887").await.unwrap();
+
+    assert_eq!(bug.id, 1906887);
+    assert!(bug.summary.is_some());
+    assert_eq!(bug.status, None);
+    assert_eq!(bug.assigned_to_detail, None);
+    assert_eq!(bug.component, Component::None);
+    assert!(bug.cc.is_empty());
+}
+
+/// Check that the comments of a bug are available, including the bug description.
+#[tokio::test]
+async fn check_comments() {
+    let instance = rh_bugzilla();
+    let comments = instance.comments("1906887").await.unwrap();
+
+    // The first comment is the bug description.
+    let description = &comments[0];
+    assert_eq!(description.count, 0);
+    assert_eq!(description.bug_id, 1906887);
+    assert!(!description.text.is_empty());
+
+    // The comments are numbered sequentially.
+    for (index, comment) in comments.iter().enumerate() {
+        assert_eq!(comment.count, index as i32);
+    }
+}
+
+/// Check that the history of a closed bug records the change of its status.
+#[tokio::test]
+async fn check_history() {
+    let instance = rh_bugzilla();
+    let history = instance.history("1906887").await.unwrap();
+
+    assert!(!history.is_empty());
+    assert!(history
+        .iter()
+        .flat_map(|entry| &entry.changes)
+        .any(|change| change.field_name == "status"));
+}
+
+/// Check that paginated search returns a single page of bugs with the pagination metadata.
+#[tokio::test]
+async fn search_paginated() {
+    let instance = rh_bugzilla();
+    let query = "component=rust&product=Fedora";
+
+    let first_page = instance.search_paginated(query, 2, 0).await.unwrap();
+    assert_eq!(first_page.bugs.len(), 2);
+    assert_eq!(first_page.offset, Some(0));
+    let total = first_page.total_matches.unwrap();
+    assert!(total > 2);
+
+    let second_page = instance.search_paginated(query, 2, 2).await.unwrap();
+    assert_eq!(second_page.offset, Some(2));
+    assert_ne!(first_page.bugs[0].id, second_page.bugs[0].id);
+
+    // A page past the end contains no bugs.
+    let past_end = instance.search_paginated(query, 2, total).await.unwrap();
+    assert!(past_end.bugs.is_empty());
+}
+
+/// Check that the search stream walks through all pages of the results.
+#[cfg(feature = "stream")]
+#[tokio::test]
+async fn search_stream() {
+    use futures_util::StreamExt;
+
+    let instance = rh_bugzilla();
+    let query = "component=rust&product=Fedora";
+
+    let total = instance
+        .search_paginated(query, 1, 0)
+        .await
+        .unwrap()
+        .total_matches
+        .unwrap();
+    // The query must span several pages of the stream.
+    assert!(total > 10);
+
+    let bugs: Vec<_> = instance.search_stream(query, 10).collect().await;
+
+    assert_eq!(bugs.len(), total as usize);
+    assert!(bugs.iter().all(Result::is_ok));
+}
+
+/// Try accessing a bug using the blocking interface, outside of an async runtime.
+#[cfg(feature = "blocking")]
+#[test]
+fn access_bug_blocking() {
+    let instance = blocking::BzInstance::at("https://bugzilla.redhat.com".to_string()).unwrap();
+    let bug = instance.bug("1906887").unwrap();
+
+    assert_eq!(bug.id, 1906887);
+}
+
+/// Check that the list of products includes a known public product.
+#[tokio::test]
+async fn list_products() {
+    let instance = rh_bugzilla();
+    let products = instance.products().await.unwrap();
+
+    let fedora = products
+        .iter()
+        .find(|product| product.name == "Fedora")
+        .unwrap();
+    assert!(fedora
+        .components
+        .iter()
+        .any(|component| component.name == "rust"));
+}
+
+/// Check that the definition of the status field lists its legal values.
+#[tokio::test]
+async fn check_field_definition() {
+    let instance = rh_bugzilla();
+    let field = instance.field("bug_status").await.unwrap();
+
+    assert_eq!(field.name, "bug_status");
+    assert!(!field.values.is_empty());
+    assert!(field.values.iter().any(|value| value.name == "CLOSED"));
+}
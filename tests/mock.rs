@@ -0,0 +1,1737 @@
+//! Tests that run against a local mock server instead of a real Bugzilla instance.
+
+mod common;
+
+use std::time::{Duration, Instant};
+
+use bugzilla_query::*;
+use common::{MockResponse, MockServer};
+
+/// A response with minimal bugs that have the specified IDs.
+fn bugs_response(ids: impl Iterator<Item = i32>) -> MockResponse {
+    let bugs: Vec<_> = ids.map(|id| serde_json::json!({ "id": id })).collect();
+    MockResponse::ok(&serde_json::json!({ "bugs": bugs }).to_string())
+}
+
+/// A response with a single, minimal bug.
+const SINGLE_BUG: &str = r#"{"bugs": [{"id": 1906887, "summary": "A bug"}]}"#;
+
+/// Check that a slow response results in the timeout error.
+#[tokio::test]
+async fn request_timeout() {
+    let server = MockServer::start(vec![
+        MockResponse::ok(SINGLE_BUG).with_delay(Duration::from_secs(5))
+    ])
+    .await;
+    let instance = BzInstance::at(server.url())
+        .unwrap()
+        .timeout(Duration::from_millis(100));
+
+    let error = instance.bug("1906887").await.unwrap_err();
+    assert!(matches!(error, BugzillaQueryError::Timeout(_)));
+}
+
+/// Check that server errors are retried until the request succeeds.
+#[tokio::test]
+async fn retry_after_server_errors() {
+    let server = MockServer::start(vec![
+        MockResponse::json(503, "{}"),
+        MockResponse::json(502, "{}"),
+        MockResponse::ok(SINGLE_BUG),
+    ])
+    .await;
+    let instance = BzInstance::at(server.url()).unwrap().retry(3);
+
+    let bug = instance.bug("1906887").await.unwrap();
+    assert_eq!(bug.id, 1906887);
+    assert_eq!(server.requests().len(), 3);
+}
+
+/// Check that the request fails after using up all retries.
+#[tokio::test]
+async fn retries_exhausted() {
+    let server = MockServer::start(vec![MockResponse::json(503, "{}")]).await;
+    let instance = BzInstance::at(server.url()).unwrap().retry(2);
+
+    let error = instance.bug("1906887").await.unwrap_err();
+    assert!(matches!(
+        error,
+        BugzillaQueryError::RetriesExhausted { attempts: 3 }
+    ));
+    assert_eq!(server.requests().len(), 3);
+}
+
+/// Check that client errors are not retried.
+#[tokio::test]
+async fn no_retry_on_client_error() {
+    let server = MockServer::start(vec![MockResponse::json(404, r#"{"bugs": []}"#)]).await;
+    let instance = BzInstance::at(server.url()).unwrap().retry(3);
+
+    let error = instance.bug("1906887").await.unwrap_err();
+    assert!(matches!(
+        error,
+        BugzillaQueryError::HttpStatus { status: 404, .. }
+    ));
+    assert_eq!(server.requests().len(), 1);
+}
+
+/// Check that an injected client keeps its settings, and that authentication applies on top.
+#[tokio::test]
+async fn injected_client() {
+    let server = MockServer::start(vec![MockResponse::ok(SINGLE_BUG)]).await;
+
+    let mut headers = reqwest::header::HeaderMap::new();
+    headers.insert("X-Shared-Client", "yes".parse().unwrap());
+    let client = reqwest::Client::builder()
+        .default_headers(headers)
+        .build()
+        .unwrap();
+
+    let instance = BzInstance::with_client(server.url(), client)
+        .unwrap()
+        .authenticate(Auth::ApiKey("secret".to_string()));
+    instance.bug("1906887").await.unwrap();
+
+    let request = &server.requests()[0];
+    assert_eq!(request.header("X-Shared-Client"), Some("yes"));
+    assert_eq!(request.header("Authorization"), Some("Bearer secret"));
+}
+
+/// Check that requests go through the configured proxy, including its credentials.
+#[tokio::test]
+async fn request_through_proxy() {
+    let proxy_server = MockServer::start(vec![MockResponse::ok(SINGLE_BUG)]).await;
+    let proxy = reqwest::Proxy::all(proxy_server.url())
+        .unwrap()
+        .basic_auth("proxy-user", "proxy-password");
+
+    // The host doesn't exist, so only the proxy can answer the request.
+    let instance = BzInstance::at("http://bugzilla.invalid".to_string())
+        .unwrap()
+        .proxy(proxy);
+    instance.bug("1906887").await.unwrap();
+
+    let request = &proxy_server.requests()[0];
+    assert!(request
+        .path
+        .starts_with("http://bugzilla.invalid/rest/bug?"));
+    assert!(request
+        .header("Proxy-Authorization")
+        .unwrap()
+        .starts_with("Basic "));
+}
+
+/// Check that requests to all endpoints identify the client in the `User-Agent` header.
+#[tokio::test]
+async fn user_agent_header() {
+    let server = MockServer::start(vec![
+        MockResponse::ok(SINGLE_BUG),
+        MockResponse::ok(r#"{"bugs": {"1906887": {"comments": []}}}"#),
+    ])
+    .await;
+
+    let instance = BzInstance::at(server.url()).unwrap();
+    instance.bug("1906887").await.unwrap();
+    let instance = instance.user_agent("my-tool/2.0".to_string());
+    instance.comments("1906887").await.unwrap();
+
+    let requests = server.requests();
+    assert_eq!(
+        requests[0].header("User-Agent"),
+        Some(concat!("bugzilla_query/", env!("CARGO_PKG_VERSION")))
+    );
+    assert_eq!(requests[1].header("User-Agent"), Some("my-tool/2.0"));
+}
+
+/// Check that logging in stores the session token and sends it with subsequent requests.
+#[tokio::test]
+async fn login_with_token() {
+    let server = MockServer::start(vec![
+        MockResponse::ok(r#"{"id": 42, "token": "42-Session"}"#),
+        MockResponse::ok(SINGLE_BUG),
+    ])
+    .await;
+
+    let mut instance = BzInstance::at(server.url()).unwrap();
+    instance
+        .login("user@example.com", "pass&word")
+        .await
+        .unwrap();
+    assert!(matches!(&instance.auth, Auth::Token(token) if token == "42-Session"));
+
+    instance.bug("1906887").await.unwrap();
+
+    let requests = server.requests();
+    assert_eq!(
+        requests[0].path,
+        "/rest/login?login=user%40example.com&password=pass%26word"
+    );
+    assert!(requests[1].path.ends_with("&token=42-Session"));
+}
+
+/// Check that a failed request doesn't reveal the credentials in the URL of the request.
+#[tokio::test]
+async fn request_error_without_credentials() {
+    // Find a local port that nothing listens on.
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let mut instance = BzInstance::at(format!("http://127.0.0.1:{port}"))
+        .unwrap()
+        .authenticate(Auth::Token("42-Secret".to_string()));
+
+    let error = instance.bug("1906887").await.unwrap_err();
+    assert!(matches!(error, BugzillaQueryError::Request(_)));
+    assert!(!format!("{error:?}").contains("42-Secret"));
+
+    let error = instance.login("user", "Secret-Password").await.unwrap_err();
+    assert!(!format!("{error:?}").contains("Secret-Password"));
+}
+
+/// Check that an error report from Bugzilla results in a structured error.
+#[tokio::test]
+async fn bugzilla_error_report() {
+    let server = MockServer::start(vec![MockResponse::ok(
+        r#"{"error": true, "message": "The search parameter is invalid.", "code": 108, "documentation": "https://bugzilla.readthedocs.org/"}"#,
+    )])
+    .await;
+    let instance = BzInstance::at(server.url()).unwrap();
+
+    let error = instance.search("invalid=query").await.unwrap_err();
+    assert!(matches!(
+        error,
+        BugzillaQueryError::Api { code: 108, message } if message == "The search parameter is invalid."
+    ));
+}
+
+/// Check that an unsuccessful response results in an error with its status and body.
+#[tokio::test]
+async fn http_status_error() {
+    let server = MockServer::start(vec![MockResponse::json(
+        500,
+        "The server is under maintenance.",
+    )])
+    .await;
+    let instance = BzInstance::at(server.url()).unwrap();
+
+    let error = instance.bug("1906887").await.unwrap_err();
+    assert!(matches!(
+        error,
+        BugzillaQueryError::HttpStatus { status: 500, body } if body == "The server is under maintenance."
+    ));
+}
+
+/// Check that rejected credentials result in the unauthorized error.
+#[tokio::test]
+async fn unauthorized_error() {
+    let server = MockServer::start(vec![MockResponse::json(
+        401,
+        r#"{"error": true, "message": "The API key you specified is invalid.", "code": 306}"#,
+    )])
+    .await;
+    let instance = BzInstance::at(server.url())
+        .unwrap()
+        .authenticate(Auth::ApiKey("invalid".to_string()));
+
+    let error = instance.bug("1906887").await.unwrap_err();
+    assert!(matches!(error, BugzillaQueryError::Unauthorized));
+}
+
+/// Check that a forbidden request results in the forbidden error.
+#[tokio::test]
+async fn forbidden_error() {
+    let server = MockServer::start(vec![MockResponse::json(403, "Forbidden")]).await;
+    let instance = BzInstance::at(server.url()).unwrap();
+
+    let error = instance.bug("1906887").await.unwrap_err();
+    assert!(matches!(error, BugzillaQueryError::Forbidden));
+}
+
+/// Check that an anonymous request for a private bug results in the forbidden error,
+/// even though Bugzilla responds with the unauthorized status.
+#[tokio::test]
+async fn private_bug_forbidden() {
+    let server = MockServer::start(vec![MockResponse::json(
+        401,
+        r#"{"error": true, "message": "You are not authorized to access bug #1.", "code": 102}"#,
+    )])
+    .await;
+    let instance = BzInstance::at(server.url()).unwrap();
+
+    let error = instance.bug("1").await.unwrap_err();
+    assert!(matches!(error, BugzillaQueryError::Forbidden));
+}
+
+/// Check that an error report from Bugzilla takes precedence over the HTTP status.
+#[tokio::test]
+async fn http_status_with_error_report() {
+    let server = MockServer::start(vec![MockResponse::json(
+        404,
+        r#"{"error": true, "message": "Bug #1 does not exist.", "code": 101}"#,
+    )])
+    .await;
+    let instance = BzInstance::at(server.url()).unwrap();
+
+    let error = instance.bugs(&["1"]).await.unwrap_err();
+    assert!(matches!(error, BugzillaQueryError::Api { code: 101, .. }));
+}
+
+/// Check that the dependency traversal stops at the maximum depth,
+/// requests each level together, and survives a cycle.
+#[tokio::test]
+async fn dependency_tree_traversal() {
+    let server = MockServer::start_with(|request| {
+        let query = request.path.split_once('?').unwrap().1;
+        let ids = query
+            .split('&')
+            .next()
+            .unwrap()
+            .strip_prefix("id=")
+            .unwrap();
+        let bugs: Vec<_> = ids
+            .split(',')
+            .map(|id| {
+                let depends_on: Vec<i32> = match id {
+                    "1" => vec![2, 3],
+                    // Bug 2 depends back on bug 1.
+                    "2" => vec![3, 1],
+                    "3" => vec![4],
+                    "4" => vec![5],
+                    _ => vec![],
+                };
+                serde_json::json!({ "id": id.parse::<i32>().unwrap(), "depends_on": depends_on })
+            })
+            .collect();
+        MockResponse::ok(&serde_json::json!({ "bugs": bugs }).to_string())
+    })
+    .await;
+    let instance = BzInstance::at(server.url()).unwrap();
+
+    let graph = instance
+        .dependency_tree("1", Direction::DependsOn, 2)
+        .await
+        .unwrap();
+
+    assert_eq!(graph.root, 1);
+    let mut ids: Vec<i32> = graph.bugs.keys().copied().collect();
+    ids.sort_unstable();
+    assert_eq!(ids, vec![1, 2, 3, 4]);
+    assert_eq!(graph.neighbors[&1], vec![2, 3]);
+    assert_eq!(graph.neighbors[&2], vec![3, 1]);
+    // Bug 5 is past the maximum depth.
+    assert_eq!(graph.neighbors[&4], Vec::<i32>::new());
+    assert_eq!(server.requests().len(), 3);
+}
+
+/// Check that user names and IDs are requested with their own parameters.
+#[tokio::test]
+async fn look_up_users() {
+    let server = MockServer::start(vec![MockResponse::ok(
+        r#"{"users": [
+            {"id": 1, "name": "user+bz@example.com", "real_name": "A User", "email": "user+bz@example.com"},
+            {"id": 42, "name": "other@example.com", "real_name": "Other User", "email": "other@example.com"}
+        ]}"#,
+    )])
+    .await;
+    let instance = BzInstance::at(server.url())
+        .unwrap()
+        .authenticate(Auth::ApiKey("key".to_string()));
+
+    let users = instance
+        .users(&["user+bz@example.com", "42"])
+        .await
+        .unwrap();
+
+    assert_eq!(users.len(), 2);
+    assert_eq!(users[1].real_name, "Other User");
+    assert_eq!(
+        server.requests()[0].path,
+        "/rest/user?names=user%2Bbz%40example.com&ids=42"
+    );
+}
+
+/// A new bug with all required fields.
+fn new_bug() -> NewBug {
+    NewBug {
+        product: "Fedora".to_string(),
+        component: "rust".to_string(),
+        summary: "A new bug".to_string(),
+        version: "36".to_string(),
+        op_sys: "Linux".to_string(),
+        platform: "All".to_string(),
+        severity: "low".to_string(),
+        ..NewBug::default()
+    }
+}
+
+/// Check that a new bug is sent as JSON without the unset fields, and that
+/// the ID of the created bug is returned.
+#[tokio::test]
+async fn create_bug() {
+    let server = MockServer::start(vec![MockResponse::ok(r#"{"id": 2100000}"#)]).await;
+    let instance = BzInstance::at(server.url())
+        .unwrap()
+        .authenticate(Auth::ApiKey("key".to_string()));
+    let mut new = new_bug();
+    new.description = Some("Steps to reproduce".to_string());
+    new.extra
+        .insert("cf_doc_type".to_string(), serde_json::json!("Bug Fix"));
+
+    let id = instance.create_bug(new).await.unwrap();
+
+    assert_eq!(id, 2100000);
+    let requests = server.requests();
+    assert_eq!(requests[0].method, "POST");
+    assert_eq!(requests[0].path, "/rest/bug");
+    let body: serde_json::Value = serde_json::from_str(&requests[0].body).unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({
+            "product": "Fedora",
+            "component": "rust",
+            "summary": "A new bug",
+            "version": "36",
+            "op_sys": "Linux",
+            "platform": "All",
+            "severity": "low",
+            "description": "Steps to reproduce",
+            "cf_doc_type": "Bug Fix",
+        })
+    );
+}
+
+/// Check that Bugzilla rejecting a new bug results in its structured error report.
+#[tokio::test]
+async fn create_bug_rejected() {
+    let server = MockServer::start(vec![MockResponse::json(
+        400,
+        r#"{"error": true, "message": "You must select/enter a component.", "code": 50}"#,
+    )])
+    .await;
+    let instance = BzInstance::at(server.url())
+        .unwrap()
+        .authenticate(Auth::ApiKey("key".to_string()));
+
+    let error = instance.create_bug(new_bug()).await.unwrap_err();
+    assert!(matches!(error, BugzillaQueryError::Api { code: 50, .. }));
+
+    let anonymous = BzInstance::at(server.url()).unwrap();
+    let error = anonymous.create_bug(new_bug()).await.unwrap_err();
+    assert!(matches!(error, BugzillaQueryError::Unauthorized));
+    assert_eq!(server.requests().len(), 1);
+}
+
+/// Check that a bug update only sends the fields that are set.
+#[tokio::test]
+async fn update_bug() {
+    let server = MockServer::start(vec![MockResponse::ok(
+        r#"{"bugs": [{"id": 1906887, "changes": {"status": {"added": "CLOSED", "removed": "NEW"}}}]}"#,
+    )])
+    .await;
+    let instance = BzInstance::at(server.url())
+        .unwrap()
+        .authenticate(Auth::ApiKey("key".to_string()));
+    let update = BugUpdate {
+        status: Some("CLOSED".to_string()),
+        resolution: Some("NOTABUG".to_string()),
+        ..BugUpdate::default()
+    };
+
+    instance.update_bug("1906887", update).await.unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests[0].method, "PUT");
+    assert_eq!(requests[0].path, "/rest/bug/1906887");
+    let body: serde_json::Value = serde_json::from_str(&requests[0].body).unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({"status": "CLOSED", "resolution": "NOTABUG"})
+    );
+}
+
+/// Check that Bugzilla rejecting a bug update results in its structured error report.
+#[tokio::test]
+async fn update_bug_rejected() {
+    let server = MockServer::start(vec![MockResponse::json(
+        400,
+        r#"{"error": true, "message": "There is no status named 'DONE'.", "code": 60}"#,
+    )])
+    .await;
+    let instance = BzInstance::at(server.url())
+        .unwrap()
+        .authenticate(Auth::ApiKey("key".to_string()));
+    let update = BugUpdate {
+        status: Some("DONE".to_string()),
+        ..BugUpdate::default()
+    };
+
+    let error = instance.update_bug("1906887", update).await.unwrap_err();
+    assert!(matches!(error, BugzillaQueryError::Api { code: 60, .. }));
+}
+
+/// Check that an error report with a successful status also results in `Api`,
+/// rather than in a successful update.
+#[tokio::test]
+async fn update_bug_rejected_with_success_status() {
+    let server = MockServer::start(vec![MockResponse::ok(
+        r#"{"error": true, "message": "There is no status named 'DONE'.", "code": 60}"#,
+    )])
+    .await;
+    let instance = BzInstance::at(server.url())
+        .unwrap()
+        .authenticate(Auth::ApiKey("key".to_string()));
+    let update = BugUpdate {
+        status: Some("DONE".to_string()),
+        ..BugUpdate::default()
+    };
+
+    let error = instance.update_bug("1906887", update).await.unwrap_err();
+    assert!(matches!(error, BugzillaQueryError::Api { code: 60, .. }));
+}
+
+/// Check that a comment is sent as JSON, including characters that URLs encode,
+/// and that the ID of the new comment is returned.
+#[tokio::test]
+async fn add_comment() {
+    let server = MockServer::start(vec![MockResponse::ok(r#"{"id": 15000000}"#)]).await;
+    let instance = BzInstance::at(server.url())
+        .unwrap()
+        .authenticate(Auth::ApiKey("key".to_string()));
+    let text = "Fixed in 1.2 & later.\nSee \"comment #1\" + 50%.";
+
+    let id = instance.add_comment("1906887", text, true).await.unwrap();
+
+    assert_eq!(id, 15000000);
+    let requests = server.requests();
+    assert_eq!(requests[0].method, "POST");
+    assert_eq!(requests[0].path, "/rest/bug/1906887/comment");
+    let body: serde_json::Value = serde_json::from_str(&requests[0].body).unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({"comment": text, "is_private": true})
+    );
+}
+
+/// Check that tags change with an authenticated `PUT` request with the tag update structure.
+#[tokio::test]
+async fn add_and_remove_tags() {
+    let server = MockServer::start(vec![MockResponse::ok(
+        r#"{"changes": {"1906887": {"added": ["triage"], "removed": []}}}"#,
+    )])
+    .await;
+    let instance = BzInstance::at(server.url())
+        .unwrap()
+        .authenticate(Auth::ApiKey("key".to_string()));
+
+    instance.add_tags("1906887", &["triage"]).await.unwrap();
+    instance.remove_tags("1906887", &["old"]).await.unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests[0].method, "PUT");
+    assert_eq!(requests[0].path, "/rest/bug/1906887/tags");
+    assert_eq!(requests[0].header("authorization"), Some("Bearer key"));
+    let body: serde_json::Value = serde_json::from_str(&requests[0].body).unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({"ids": ["1906887"], "tags": {"add": ["triage"]}})
+    );
+    let body: serde_json::Value = serde_json::from_str(&requests[1].body).unwrap();
+    assert_eq!(body["tags"], serde_json::json!({"remove": ["old"]}));
+}
+
+/// Check that a rejected tag change results in `Api` even with a successful status.
+#[tokio::test]
+async fn tags_rejected_with_success_status() {
+    let server = MockServer::start(vec![MockResponse::ok(
+        r#"{"error": true, "message": "Bug #1906887 does not exist.", "code": 101}"#,
+    )])
+    .await;
+    let instance = BzInstance::at(server.url())
+        .unwrap()
+        .authenticate(Auth::ApiKey("key".to_string()));
+
+    let error = instance.add_tags("1906887", &["triage"]).await.unwrap_err();
+    assert!(matches!(error, BugzillaQueryError::Api { code: 101, .. }));
+}
+
+/// Check that changing tags without credentials fails without sending a request.
+#[tokio::test]
+async fn anonymous_tags_unauthorized() {
+    let server = MockServer::start(vec![MockResponse::ok("{}")]).await;
+    let instance = BzInstance::at(server.url()).unwrap();
+
+    let error = instance.add_tags("1906887", &["triage"]).await.unwrap_err();
+    assert!(matches!(error, BugzillaQueryError::Unauthorized));
+    assert!(server.requests().is_empty());
+}
+
+/// Check that the bugs are keyed by their IDs, and that the IDs
+/// that Bugzilla didn't return are reported.
+#[tokio::test]
+async fn bugs_keyed_by_id() {
+    let server = MockServer::start(vec![MockResponse::ok(
+        r#"{"bugs": [{"id": 1906883}, {"id": 1906887, "alias": ["corn"]}]}"#,
+    )])
+    .await;
+    let instance = BzInstance::at(server.url()).unwrap();
+
+    let bugs = instance.bugs_map(&["1906883", "corn"]).await.unwrap();
+    let mut ids: Vec<i32> = bugs.keys().copied().collect();
+    ids.sort_unstable();
+    assert_eq!(ids, vec![1906883, 1906887]);
+
+    let error = instance
+        .bugs_map(&["1906883", "1906887", "111111111"])
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        BugzillaQueryError::MissingBugs(missing) if missing == vec!["111111111"]
+    ));
+}
+
+/// Check that the strict mode reports the IDs that Bugzilla didn't return,
+/// while the default mode leaves them out.
+#[tokio::test]
+async fn strict_missing_bugs() {
+    let server = MockServer::start(vec![MockResponse::ok(SINGLE_BUG)]).await;
+
+    let instance = BzInstance::at(server.url()).unwrap();
+    let bugs = instance.bugs(&["1906887", "111111111"]).await.unwrap();
+    assert_eq!(bugs.len(), 1);
+
+    let instance = instance.strict(true);
+    let error = instance.bugs(&["1906887", "111111111"]).await.unwrap_err();
+    assert!(matches!(
+        error,
+        BugzillaQueryError::MissingBugs(missing) if missing == vec!["111111111"]
+    ));
+    assert_eq!(instance.bugs(&["1906887"]).await.unwrap().len(), 1);
+}
+
+/// Check that a repeated request sends the `ETag` of the cached response,
+/// and that an unchanged response uses the cached copy.
+#[cfg(feature = "cache")]
+#[tokio::test]
+async fn cached_response() {
+    let server = MockServer::start(vec![
+        MockResponse::ok(SINGLE_BUG).with_header("ETag", "\"abc\""),
+        MockResponse::json(304, ""),
+    ])
+    .await;
+    let instance = BzInstance::at(server.url()).unwrap().with_cache();
+
+    let first = instance.bug("1906887").await.unwrap();
+    let second = instance.bug("1906887").await.unwrap();
+
+    assert_eq!(first, second);
+    let requests = server.requests();
+    assert_eq!(requests[0].header("if-none-match"), None);
+    assert_eq!(requests[1].header("if-none-match"), Some("\"abc\""));
+}
+
+/// Check that an unchanged response without a cached copy results in a full request,
+/// rather than parsing the empty body.
+#[cfg(feature = "cache")]
+#[tokio::test]
+async fn not_modified_without_cached_copy() {
+    let server = MockServer::start(vec![
+        MockResponse::json(304, ""),
+        MockResponse::ok(SINGLE_BUG),
+    ])
+    .await;
+    let instance = BzInstance::at(server.url()).unwrap().with_cache();
+
+    let bug = instance.bug("1906887").await.unwrap();
+
+    assert_eq!(bug.id, 1906887);
+    assert_eq!(server.requests().len(), 2);
+}
+
+/// Check that the login response isn't cached, because its URL contains the password.
+#[cfg(feature = "cache")]
+#[tokio::test]
+async fn login_not_cached() {
+    let server = MockServer::start(vec![MockResponse::ok(
+        r#"{"id": 42, "token": "42-Session"}"#,
+    )
+    .with_header("ETag", "\"abc\"")])
+    .await;
+    let mut instance = BzInstance::at(server.url()).unwrap().with_cache();
+
+    instance
+        .login("user@example.com", "password")
+        .await
+        .unwrap();
+    instance
+        .login("user@example.com", "password")
+        .await
+        .unwrap();
+
+    assert_eq!(server.requests()[1].header("if-none-match"), None);
+}
+
+/// Check that the custom headers are sent along with the authentication header.
+#[tokio::test]
+async fn custom_headers() {
+    let server = MockServer::start(vec![MockResponse::ok(SINGLE_BUG)]).await;
+    let instance = BzInstance::at(server.url())
+        .unwrap()
+        .authenticate(Auth::ApiKey("key".to_string()))
+        .header("X-Forwarded-User".to_string(), "user".to_string())
+        .header("X-CDN-Bypass".to_string(), "token".to_string());
+
+    instance.bug("1906887").await.unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests[0].header("x-forwarded-user"), Some("user"));
+    assert_eq!(requests[0].header("x-cdn-bypass"), Some("token"));
+    assert_eq!(requests[0].header("authorization"), Some("Bearer key"));
+}
+
+/// Check that the JSON-RPC transport sends `Bug.get` in the JSON-RPC structure,
+/// and that it unwraps the result from the envelope.
+#[tokio::test]
+async fn json_rpc_bug_get() {
+    let server = MockServer::start(vec![MockResponse::ok(
+        r#"{"result": {"bugs": [{"id": 1906887, "summary": "A bug"}]}, "error": null, "id": 1}"#,
+    )])
+    .await;
+    let instance = BzInstance::at(server.url())
+        .unwrap()
+        .transport(Transport::JsonRpc)
+        .authenticate(Auth::ApiKey("key".to_string()));
+
+    let bug = instance.bug("1906887").await.unwrap();
+
+    assert_eq!(bug.summary.as_deref(), Some("A bug"));
+    let requests = server.requests();
+    assert_eq!(requests[0].method, "POST");
+    assert_eq!(requests[0].path, "/jsonrpc.cgi");
+    let body: serde_json::Value = serde_json::from_str(&requests[0].body).unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({
+            "method": "Bug.get",
+            "params": [{
+                "ids": ["1906887"],
+                "include_fields": ["_default"],
+                "Bugzilla_api_key": "key",
+            }],
+            "id": 1,
+        })
+    );
+}
+
+/// Check that the JSON-RPC transport converts a free-form query to parameters,
+/// and that an error in the envelope results in the structured error report.
+#[tokio::test]
+async fn json_rpc_search_error() {
+    let server = MockServer::start(vec![MockResponse::ok(
+        r#"{"result": null, "error": {"code": 108, "message": "The search parameter is invalid."}, "id": 1}"#,
+    )])
+    .await;
+    let instance = BzInstance::at(server.url())
+        .unwrap()
+        .transport(Transport::JsonRpc)
+        .paginate(Pagination::Limit(5));
+
+    let error = instance
+        .search("product=Fedora&component=rust&component=C%2B%2B")
+        .await
+        .unwrap_err();
+
+    assert!(matches!(error, BugzillaQueryError::Api { code: 108, .. }));
+    let body: serde_json::Value = serde_json::from_str(&server.requests()[0].body).unwrap();
+    assert_eq!(body["method"], "Bug.search");
+    assert_eq!(
+        body["params"][0],
+        serde_json::json!({
+            "product": "Fedora",
+            "component": ["rust", "C++"],
+            "include_fields": ["_default"],
+            "limit": 5,
+        })
+    );
+}
+
+/// Check that the alias lookup, the structured search, the quick search, the paginated search,
+/// and counting all use the JSON-RPC transport.
+#[tokio::test]
+async fn json_rpc_search_methods() {
+    let server = MockServer::start(vec![MockResponse::ok(
+        r#"{"result": {"bugs": [{"id": 1906887}], "total_matches": 1}, "error": null, "id": 1}"#,
+    )])
+    .await;
+    let instance = BzInstance::at(server.url())
+        .unwrap()
+        .transport(Transport::JsonRpc);
+
+    instance.bug_by_alias("stylo").await.unwrap();
+    instance
+        .search_with(SearchBuilder::new().param("product", "Fedora"))
+        .await
+        .unwrap();
+    instance.quicksearch("rust crash").await.unwrap();
+    instance
+        .search_paginated("product=Fedora", 10, 20)
+        .await
+        .unwrap();
+    assert_eq!(instance.count("product=Fedora").await.unwrap(), 1);
+
+    let params: Vec<serde_json::Value> = server
+        .requests()
+        .iter()
+        .map(|request| {
+            assert_eq!(request.path, "/jsonrpc.cgi");
+            let body: serde_json::Value = serde_json::from_str(&request.body).unwrap();
+            body["params"][0].clone()
+        })
+        .collect();
+    assert_eq!(params[0]["ids"], serde_json::json!(["stylo"]));
+    assert_eq!(params[1]["product"], "Fedora");
+    assert_eq!(params[2]["quicksearch"], "rust crash");
+    assert_eq!(params[3]["limit"], 10);
+    assert_eq!(params[3]["offset"], 20);
+    assert_eq!(params[4]["include_fields"], serde_json::json!(["id"]));
+    assert_eq!(params[4]["limit"], 1);
+}
+
+/// Check that the public comments leave out a private comment, but keep
+/// the public bug description.
+#[tokio::test]
+async fn public_comments() {
+    let comment = |id: i32, count: i32, is_private: bool| {
+        serde_json::json!({
+            "id": id,
+            "bug_id": 1906887,
+            "attachment_id": null,
+            "count": count,
+            "text": format!("Comment {count}"),
+            "creator": "user@example.com",
+            "time": "2022-05-01T12:30:00Z",
+            "creation_time": "2022-05-01T12:30:00Z",
+            "is_private": is_private,
+        })
+    };
+    let body = serde_json::json!({
+        "bugs": {"1906887": {"comments": [comment(1, 0, false), comment(2, 1, true), comment(3, 2, false)]}}
+    });
+    let server = MockServer::start(vec![MockResponse::ok(&body.to_string())]).await;
+    let instance = BzInstance::at(server.url()).unwrap();
+
+    let comments = instance.public_comments("1906887").await.unwrap();
+
+    let counts: Vec<i32> = comments.iter().map(|comment| comment.count).collect();
+    assert_eq!(counts, vec![0, 2]);
+}
+
+/// Check that the target of a duplicate is requested, and that a bug
+/// that isn't a duplicate sends no request.
+#[tokio::test]
+async fn resolve_duplicate() {
+    let server = MockServer::start(vec![MockResponse::ok(
+        r#"{"bugs": [{"id": 1906883, "dupe_of": 1906880}]}"#,
+    )])
+    .await;
+    let instance = BzInstance::at(server.url()).unwrap();
+    let duplicate = Bug {
+        id: 1906887,
+        dupe_of: Some(1906883),
+        ..Bug::default()
+    };
+
+    let target = instance.duplicate_of(&duplicate).await.unwrap().unwrap();
+
+    // Only a single step is followed, even though the target is also a duplicate.
+    assert_eq!(target.id, 1906883);
+    assert_eq!(server.requests().len(), 1);
+    assert!(server.requests()[0]
+        .path
+        .starts_with("/rest/bug?id=1906883&"));
+
+    let original = Bug {
+        id: 1906880,
+        ..Bug::default()
+    };
+    assert_eq!(instance.duplicate_of(&original).await.unwrap(), None);
+    assert_eq!(server.requests().len(), 1);
+}
+
+/// Check that the immediate dependencies of a bug are requested in a single request,
+/// and that a bug without dependencies sends no request.
+#[tokio::test]
+async fn immediate_dependencies() {
+    let server = MockServer::start(vec![
+        bugs_response([1906880, 1906881].into_iter()),
+        bugs_response([1906890].into_iter()),
+    ])
+    .await;
+    let instance = BzInstance::at(server.url()).unwrap();
+    let bug = Bug {
+        id: 1906883,
+        depends_on: vec![1906880, 1906881],
+        blocks: vec![1906890],
+        ..Bug::default()
+    };
+
+    let blockers = instance.blockers(&bug).await.unwrap();
+    let blocked = instance.blocked_by(&bug).await.unwrap();
+
+    assert_eq!(
+        blockers.iter().map(|bug| bug.id).collect::<Vec<_>>(),
+        [1906880, 1906881]
+    );
+    assert_eq!(
+        blocked.iter().map(|bug| bug.id).collect::<Vec<_>>(),
+        [1906890]
+    );
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    assert!(requests[0]
+        .path
+        .starts_with("/rest/bug?id=1906880,1906881&"));
+    assert!(requests[1].path.starts_with("/rest/bug?id=1906890&"));
+
+    let independent = Bug {
+        id: 1906887,
+        ..Bug::default()
+    };
+    assert!(instance.blockers(&independent).await.unwrap().is_empty());
+    assert!(instance.blocked_by(&independent).await.unwrap().is_empty());
+    assert_eq!(server.requests().len(), 2);
+}
+
+/// Check that a long list of IDs splits into several requests, and that the bugs
+/// come back in the original order.
+#[tokio::test]
+async fn chunk_long_id_list() {
+    let server = MockServer::start(vec![
+        bugs_response(1..=200),
+        bugs_response(201..=400),
+        bugs_response(401..=450),
+    ])
+    .await;
+    let instance = BzInstance::at(server.url()).unwrap();
+
+    let ids: Vec<String> = (1..=450).map(|id| id.to_string()).collect();
+    let ids: Vec<&str> = ids.iter().map(String::as_str).collect();
+    let bugs = instance.bugs(&ids).await.unwrap();
+
+    let returned_ids: Vec<i32> = bugs.iter().map(|bug| bug.id).collect();
+    assert_eq!(returned_ids, (1..=450).collect::<Vec<_>>());
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 3);
+    assert!(requests[0].path.contains("id=1,2,3,"));
+    assert!(requests[2].path.contains("id=401,"));
+    assert!(!requests[2].path.contains(",400,"));
+}
+
+/// Check that chunks download concurrently up to the limit, and that the bugs
+/// come back in the original order even if later chunks finish first.
+#[tokio::test]
+async fn concurrent_chunks() {
+    let server = MockServer::start_with(|request| {
+        // Reply with the requested bug, and make the earlier bugs slower.
+        let id: i32 = request.path.split("id=").nth(1).unwrap()[..2]
+            .trim_end_matches('&')
+            .parse()
+            .unwrap();
+        let delay = Duration::from_millis(20 * (20 - id as u64));
+        bugs_response(std::iter::once(id)).with_delay(delay)
+    })
+    .await;
+    let instance = BzInstance::at(server.url())
+        .unwrap()
+        .chunk_size(1)
+        .max_concurrency(3);
+
+    let ids: Vec<String> = (10..20).map(|id| id.to_string()).collect();
+    let ids: Vec<&str> = ids.iter().map(String::as_str).collect();
+    let bugs = instance.bugs(&ids).await.unwrap();
+
+    let returned_ids: Vec<i32> = bugs.iter().map(|bug| bug.id).collect();
+    assert_eq!(returned_ids, (10..20).collect::<Vec<_>>());
+    assert_eq!(server.max_in_flight(), 3);
+}
+
+/// Check that a rate-limited request waits for the delay in the `Retry-After` header.
+#[tokio::test]
+async fn retry_after_rate_limit() {
+    let server = MockServer::start(vec![
+        MockResponse::json(429, "{}").with_header("Retry-After", "1"),
+        MockResponse::ok(SINGLE_BUG),
+    ])
+    .await;
+    let instance = BzInstance::at(server.url()).unwrap().retry(1);
+    assert_eq!(instance.last_retry_after(), None);
+
+    let start = Instant::now();
+    instance.bug("1906887").await.unwrap();
+
+    assert!(start.elapsed() >= Duration::from_secs(1));
+    assert_eq!(instance.last_retry_after(), Some(Duration::from_secs(1)));
+    assert_eq!(server.requests().len(), 2);
+}
+
+/// Check that the `Retry-After` header also works as an HTTP date.
+#[tokio::test]
+async fn retry_after_http_date() {
+    let server = MockServer::start(vec![
+        MockResponse::json(503, "{}").with_header("Retry-After", "Wed, 21 Oct 2015 07:28:00 GMT"),
+        MockResponse::ok(SINGLE_BUG),
+    ])
+    .await;
+    let instance = BzInstance::at(server.url()).unwrap().retry(1);
+
+    instance.bug("1906887").await.unwrap();
+
+    // The date is in the past, so the request repeats right away.
+    assert_eq!(instance.last_retry_after(), Some(Duration::ZERO));
+}
+
+/// Check that a custom REST API path replaces `/rest`, without doubling the slashes.
+#[tokio::test]
+async fn custom_rest_path() {
+    let server = MockServer::start(vec![MockResponse::ok(SINGLE_BUG)]).await;
+
+    for path in ["/bugzilla/rest", "bugzilla/rest/", "/bugzilla/rest/"] {
+        let instance = BzInstance::at(format!("{}/", server.url()))
+            .unwrap()
+            .rest_path(path.to_string());
+        instance.bug("1906887").await.unwrap();
+    }
+
+    for request in server.requests() {
+        assert!(request.path.starts_with("/bugzilla/rest/bug?id=1906887"));
+    }
+}
+
+/// Check that the excluded fields follow the included fields in the URL.
+#[tokio::test]
+async fn excluded_fields_query() {
+    let server = MockServer::start(vec![MockResponse::ok(SINGLE_BUG)]).await;
+    let instance = BzInstance::at(server.url())
+        .unwrap()
+        .include_fields(vec!["_all".to_string()])
+        .exclude_fields(vec!["cc_detail".to_string(), "flags".to_string()]);
+
+    instance.bug("1906887").await.unwrap();
+
+    assert_eq!(
+        server.requests()[0].path,
+        "/rest/bug?id=1906887&include_fields=_all&exclude_fields=cc_detail,flags"
+    );
+}
+
+/// Check that the IDs and aliases with reserved characters reach the server intact.
+#[tokio::test]
+async fn encoded_ids_and_alias() {
+    let server = MockServer::start(vec![MockResponse::ok(SINGLE_BUG)]).await;
+    let instance = BzInstance::at(server.url()).unwrap();
+
+    instance.bugs(&["1906887", "a&b+c#d=e"]).await.unwrap();
+    instance.bug_by_alias("a&b+c#d=e").await.unwrap();
+
+    let requests = server.requests();
+    assert!(requests[0]
+        .path
+        .starts_with("/rest/bug?id=1906887,a%26b%2Bc%23d%3De&"));
+    assert!(requests[1]
+        .path
+        .starts_with("/rest/bug?alias=a%26b%2Bc%23d%3De&"));
+}
+
+/// Check that the values in a free-form search query are percent-encoded.
+#[tokio::test]
+async fn encoded_search_query() {
+    let server = MockServer::start(vec![MockResponse::ok(SINGLE_BUG)]).await;
+    let instance = BzInstance::at(server.url()).unwrap();
+
+    instance
+        .search("summary=foo bar&component=C/C%2B%2B&whiteboard=100%25")
+        .await
+        .unwrap();
+
+    let requests = server.requests();
+    assert!(requests[0]
+        .path
+        .starts_with("/rest/bug?summary=foo%20bar&component=C%2FC%2B%2B&whiteboard=100%25"));
+}
+
+/// Check that `+` in a free-form query stands for a space, like in the queries
+/// that the Bugzilla web UI shows, in both transports.
+#[tokio::test]
+async fn plus_as_space_in_query() {
+    let server = MockServer::start(vec![MockResponse::ok(SINGLE_BUG)]).await;
+    let instance = BzInstance::at(server.url()).unwrap();
+
+    instance.search("summary=foo+bar").await.unwrap();
+
+    assert!(server.requests()[0]
+        .path
+        .starts_with("/rest/bug?summary=foo%20bar&"));
+
+    let server = MockServer::start(vec![MockResponse::ok(
+        r#"{"result": {"bugs": [{"id": 1906887}]}, "error": null, "id": 1}"#,
+    )])
+    .await;
+    let instance = BzInstance::at(server.url())
+        .unwrap()
+        .transport(Transport::JsonRpc);
+
+    instance.search("summary=foo+bar").await.unwrap();
+
+    let body: serde_json::Value = serde_json::from_str(&server.requests()[0].body).unwrap();
+    assert_eq!(body["params"][0]["summary"], "foo bar");
+}
+
+/// Check that the full search result keeps the pagination metadata.
+#[tokio::test]
+async fn search_full_metadata() {
+    let server = MockServer::start(vec![MockResponse::ok(
+        r#"{"bugs": [{"id": 1}, {"id": 2}], "total_matches": 10, "offset": 0, "limit": "2"}"#,
+    )])
+    .await;
+    let instance = BzInstance::at(server.url())
+        .unwrap()
+        .paginate(Pagination::Limit(2));
+
+    let result = instance.search_full("product=Fedora").await.unwrap();
+
+    assert_eq!(result.bugs.len(), 2);
+    assert_eq!(result.total_matches, Some(10));
+    assert_eq!(result.offset, Some(0));
+    assert_eq!(result.limit.as_deref(), Some("2"));
+    assert!(result.is_truncated());
+    assert!(server.requests()[0].path.ends_with("&limit=2"));
+}
+
+/// Check that searching for all bugs follows the offset over several pages
+/// until it reaches the total number of matches.
+#[tokio::test]
+async fn search_all_pages() {
+    let server = MockServer::start(vec![
+        MockResponse::ok(r#"{"bugs": [{"id": 1}, {"id": 2}], "total_matches": 5}"#),
+        MockResponse::ok(r#"{"bugs": [{"id": 3}, {"id": 4}], "total_matches": 5}"#),
+        MockResponse::ok(r#"{"bugs": [{"id": 5}], "total_matches": 5}"#),
+    ])
+    .await;
+    let instance = BzInstance::at(server.url()).unwrap();
+
+    let bugs = instance.search_all("product=Fedora", 2).await.unwrap();
+
+    let ids: Vec<i32> = bugs.iter().map(|bug| bug.id).collect();
+    assert_eq!(ids, [1, 2, 3, 4, 5]);
+    let requests = server.requests();
+    assert_eq!(requests.len(), 3);
+    assert!(requests[0].path.ends_with("&limit=2&offset=0"));
+    assert!(requests[1].path.ends_with("&limit=2&offset=2"));
+    assert!(requests[2].path.ends_with("&limit=2&offset=4"));
+}
+
+/// Check that a limited search sends its own limit and keeps the configured pagination.
+#[tokio::test]
+async fn search_limited() {
+    let server = MockServer::start(vec![bugs_response([1, 2, 3].into_iter())]).await;
+    let instance = BzInstance::at(server.url())
+        .unwrap()
+        .paginate(Pagination::Unlimited);
+
+    let bugs = instance.search_limited("product=Fedora", 3).await.unwrap();
+
+    assert_eq!(bugs.len(), 3);
+    assert!(server.requests()[0]
+        .path
+        .starts_with("/rest/bug?product=Fedora&"));
+    assert!(server.requests()[0].path.ends_with("&limit=3"));
+    assert!(matches!(instance.pagination, Pagination::Unlimited));
+}
+
+/// Check that searching for all bugs doesn't skip any bugs if the server returns
+/// fewer bugs than the requested page size.
+#[tokio::test]
+async fn search_all_capped_pages() {
+    let server = MockServer::start(vec![
+        MockResponse::ok(r#"{"bugs": [{"id": 1}], "total_matches": 3}"#),
+        MockResponse::ok(r#"{"bugs": [{"id": 2}], "total_matches": 3}"#),
+        MockResponse::ok(r#"{"bugs": [{"id": 3}], "total_matches": 3}"#),
+    ])
+    .await;
+    let instance = BzInstance::at(server.url()).unwrap();
+
+    let bugs = instance.search_all("product=Fedora", 2).await.unwrap();
+
+    let ids: Vec<i32> = bugs.iter().map(|bug| bug.id).collect();
+    assert_eq!(ids, [1, 2, 3]);
+    let requests = server.requests();
+    assert_eq!(requests.len(), 3);
+    assert!(requests[1].path.ends_with("&limit=2&offset=1"));
+    assert!(requests[2].path.ends_with("&limit=2&offset=2"));
+}
+
+/// Check that searching for all bugs stops if the server ignores the offset
+/// and doesn't report the number of matches.
+#[tokio::test]
+async fn search_all_ignored_offset() {
+    let server = MockServer::start(vec![MockResponse::ok(
+        r#"{"bugs": [{"id": 1}, {"id": 2}]}"#,
+    )])
+    .await;
+    let instance = BzInstance::at(server.url()).unwrap();
+
+    let bugs = instance.search_all("product=Fedora", 2).await.unwrap();
+
+    let ids: Vec<i32> = bugs.iter().map(|bug| bug.id).collect();
+    assert_eq!(ids, [1, 2]);
+    assert_eq!(server.requests().len(), 2);
+}
+
+/// Check that the search stream continues after the bugs that the server returned,
+/// even if the server caps the page below the requested size.
+#[cfg(feature = "stream")]
+#[tokio::test]
+async fn search_stream_capped_pages() {
+    use futures_util::StreamExt;
+
+    let server = MockServer::start(vec![
+        MockResponse::ok(r#"{"bugs": [{"id": 1}], "total_matches": 3}"#),
+        MockResponse::ok(r#"{"bugs": [{"id": 2}], "total_matches": 3}"#),
+        MockResponse::ok(r#"{"bugs": [{"id": 3}], "total_matches": 3}"#),
+    ])
+    .await;
+    let instance = BzInstance::at(server.url()).unwrap();
+
+    let bugs: Vec<_> = instance.search_stream("product=Fedora", 2).collect().await;
+
+    let ids: Vec<i32> = bugs.into_iter().map(|bug| bug.unwrap().id).collect();
+    assert_eq!(ids, [1, 2, 3]);
+    let requests = server.requests();
+    assert_eq!(requests.len(), 3);
+    assert!(requests[1].path.ends_with("&limit=2&offset=1"));
+    assert!(requests[2].path.ends_with("&limit=2&offset=2"));
+}
+
+/// Check that counting requests a single bug and returns the number of all matches.
+#[tokio::test]
+async fn count_matches() {
+    let server = MockServer::start(vec![MockResponse::ok(
+        r#"{"bugs": [{"id": 1}], "total_matches": 42}"#,
+    )])
+    .await;
+    let instance = BzInstance::at(server.url()).unwrap();
+
+    assert_eq!(instance.count("product=Fedora").await.unwrap(), 42);
+    let requests = server.requests();
+    assert_eq!(
+        requests[0].path,
+        "/rest/bug?product=Fedora&include_fields=id&limit=1"
+    );
+}
+
+/// Check that counting falls back to listing the IDs if Bugzilla doesn't report
+/// the number of matches, and that no matches result in 0.
+#[tokio::test]
+async fn count_without_total_matches() {
+    let server = MockServer::start(vec![
+        MockResponse::ok(r#"{"bugs": [{"id": 1}]}"#),
+        bugs_response(1..=3),
+        MockResponse::ok(r#"{"bugs": [], "total_matches": 0}"#),
+    ])
+    .await;
+    let instance = BzInstance::at(server.url()).unwrap();
+
+    assert_eq!(instance.count("product=Fedora").await.unwrap(), 3);
+    assert_eq!(
+        server.requests()[1].path,
+        "/rest/bug?product=Fedora&include_fields=id&limit=0"
+    );
+    assert_eq!(instance.count("product=None").await.unwrap(), 0);
+}
+
+/// Check that counting uses the default scope of the instance, like the search does.
+#[tokio::test]
+async fn count_default_scope() {
+    let server = MockServer::start(vec![
+        MockResponse::ok(r#"{"bugs": [{"id": 1}], "total_matches": 2}"#),
+        bugs_response(1..=2),
+    ])
+    .await;
+    let instance = BzInstance::at(server.url())
+        .unwrap()
+        .default_product("Fedora".to_string())
+        .default_component("rust".to_string());
+
+    let count = instance.count("status=NEW").await.unwrap();
+    let bugs = instance.search("status=NEW").await.unwrap();
+
+    assert_eq!(count as usize, bugs.len());
+    let requests = server.requests();
+    assert_eq!(
+        requests[0].path,
+        "/rest/bug?status=NEW&product=Fedora&component=rust&include_fields=id&limit=1"
+    );
+    assert!(requests[1]
+        .path
+        .starts_with("/rest/bug?status=NEW&product=Fedora&component=rust"));
+}
+
+/// Check that the time filter keeps the base query and formats the timestamp for Bugzilla.
+#[tokio::test]
+async fn search_changed_since_timestamp() {
+    let server = MockServer::start(vec![MockResponse::ok(r#"{"bugs": []}"#)]).await;
+    let instance = BzInstance::at(server.url()).unwrap();
+    let since = chrono::DateTime::parse_from_rfc3339("2022-05-01T14:30:00+02:00")
+        .unwrap()
+        .with_timezone(&chrono::Utc);
+
+    let bugs = instance
+        .search_changed_since("product=Fedora", since)
+        .await
+        .unwrap();
+
+    assert!(bugs.is_empty());
+    let requests = server.requests();
+    assert!(requests[0]
+        .path
+        .starts_with("/rest/bug?product=Fedora&last_change_time=2022-05-01T12%3A30%3A00Z"));
+}
+
+/// Check that a quicksearch is encoded as a single query parameter.
+#[tokio::test]
+async fn encoded_quicksearch() {
+    let server = MockServer::start(vec![MockResponse::ok(SINGLE_BUG)]).await;
+    let instance = BzInstance::at(server.url()).unwrap();
+
+    instance
+        .quicksearch("ALL product:Fedora component:rust")
+        .await
+        .unwrap();
+
+    let requests = server.requests();
+    assert!(requests[0]
+        .path
+        .starts_with("/rest/bug?quicksearch=ALL%20product%3AFedora%20component%3Arust"));
+}
+
+/// Check that the clones of an instance keep the configuration
+/// and issue requests from separate tasks.
+#[tokio::test]
+async fn cloned_instance() {
+    let server = MockServer::start(vec![MockResponse::ok(SINGLE_BUG)]).await;
+    let instance = BzInstance::at(server.url())
+        .unwrap()
+        .authenticate(Auth::ApiKey("key".to_string()));
+
+    let tasks: Vec<_> = (0..2)
+        .map(|_| {
+            let instance = instance.clone();
+            tokio::spawn(async move { instance.bug("1906887").await })
+        })
+        .collect();
+
+    for task in tasks {
+        let bug = task.await.unwrap().unwrap();
+        assert_eq!(bug.id, 1906887);
+    }
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    for request in requests {
+        assert_eq!(request.header("authorization"), Some("Bearer key"));
+    }
+}
+
+/// Check that several searches return their results in the order of the queries,
+/// even if the responses arrive in a different order.
+#[tokio::test]
+async fn search_many_queries() {
+    let server = MockServer::start_with(|request| {
+        if request.path.contains("component=rust") {
+            bugs_response([1, 2].into_iter()).with_delay(Duration::from_millis(200))
+        } else {
+            bugs_response([3].into_iter())
+        }
+    })
+    .await;
+    let instance = BzInstance::at(server.url()).unwrap();
+
+    let results = instance
+        .search_many(&["component=rust", "component=python"])
+        .await
+        .unwrap();
+
+    let ids: Vec<Vec<i32>> = results
+        .iter()
+        .map(|bugs| bugs.iter().map(|bug| bug.id).collect())
+        .collect();
+    assert_eq!(ids, vec![vec![1, 2], vec![3]]);
+    assert_eq!(server.requests().len(), 2);
+
+    assert!(instance.search_many(&[]).await.unwrap().is_empty());
+    assert_eq!(server.requests().len(), 2);
+}
+
+/// Check that the HTTP/2 setting reaches the client: the mock server only speaks HTTP/1.1,
+/// so requests fail with HTTP/2 forced, and succeed again after disabling it.
+#[tokio::test]
+async fn http2_prior_knowledge() {
+    let server = MockServer::start(vec![MockResponse::ok(SINGLE_BUG)]).await;
+    let instance = BzInstance::at(server.url())
+        .unwrap()
+        .timeout(Duration::from_secs(2))
+        .http2_prior_knowledge(true);
+
+    let error = instance.bug("1906887").await.unwrap_err();
+    assert!(matches!(error, BugzillaQueryError::Request(_)));
+
+    let instance = instance.http2_prior_knowledge(false);
+    let bug = instance.bug("1906887").await.unwrap();
+    assert_eq!(bug.id, 1906887);
+}
+
+/// A response from Red Hat Bugzilla with a single, complete bug.
+const BUG_FIXTURE: &str = include_str!("fixtures/bug.json");
+/// An error report from Red Hat Bugzilla for a bug that doesn't exist.
+const ERROR_FIXTURE: &str = include_str!("fixtures/error.json");
+/// A response from Red Hat Bugzilla to a search that matches several bugs.
+const SEARCH_FIXTURE: &str = include_str!("fixtures/search.json");
+
+/// Check that a complete bug from a real response deserializes from the expected URL.
+#[tokio::test]
+async fn fixture_bug() {
+    let server = MockServer::start(vec![MockResponse::ok(BUG_FIXTURE)]).await;
+    let instance = BzInstance::at(server.url()).unwrap();
+
+    let bug = instance.bug("1906883").await.unwrap();
+
+    assert_eq!(
+        server.requests()[0].path,
+        "/rest/bug?id=1906883&include_fields=_default"
+    );
+    assert_eq!(bug.id, 1906883);
+    assert_eq!(bug.status, Some(Status::Closed));
+    assert_eq!(bug.resolution_raw(), Some("CURRENTRELEASE"));
+    assert_eq!(bug.component.clone().into_vec(), ["Package Review"]);
+    assert_eq!(
+        bug.assigned_to_detail
+            .as_ref()
+            .map(|user| user.real_name.as_str()),
+        Some("Marek Suchánek")
+    );
+    assert_eq!(bug.blocks, [177841]);
+    assert_eq!(bug.deadline, None);
+    assert!(bug.creation_time.is_some());
+    assert_eq!(bug.extra["cf_doc_type"], "If docs needed, set a value");
+}
+
+/// Check that an error report from a real response maps to the API error.
+#[tokio::test]
+async fn fixture_error() {
+    let server = MockServer::start(vec![MockResponse::json(404, ERROR_FIXTURE)]).await;
+    let instance = BzInstance::at(server.url()).unwrap();
+
+    let error = instance.bugs(&["999999999"]).await.unwrap_err();
+
+    match error {
+        BugzillaQueryError::Api { code, message } => {
+            assert_eq!(code, 101);
+            assert_eq!(message, "Bug #999999999 does not exist.");
+        }
+        other => panic!("Unexpected error: {other:?}"),
+    }
+}
+
+/// Check that a search with several bugs from a real response deserializes
+/// from the expected URL, along with the pagination metadata.
+#[tokio::test]
+async fn fixture_search() {
+    let server = MockServer::start(vec![MockResponse::ok(SEARCH_FIXTURE)]).await;
+    let instance = BzInstance::at(server.url())
+        .unwrap()
+        .paginate(Pagination::Limit(20));
+
+    let result = instance
+        .search_full("component=rust-bugzilla_query&product=Fedora")
+        .await
+        .unwrap();
+
+    assert_eq!(
+        server.requests()[0].path,
+        "/rest/bug?component=rust-bugzilla_query&product=Fedora&include_fields=_default&limit=20"
+    );
+    let ids: Vec<i32> = result.bugs.iter().map(|bug| bug.id).collect();
+    assert_eq!(ids, [2004636, 2004637, 2004638]);
+    assert_eq!(result.total_matches, Some(3));
+    assert_eq!(result.bugs[0].resolution, Some(Resolution::Empty));
+    assert!(result.bugs[2].is_duplicate());
+    assert_eq!(result.bugs[2].dupe_of, Some(2004636));
+}
+
+/// Check that the searches by user send the encoded email and that no matches are empty.
+#[tokio::test]
+async fn search_by_user() {
+    let server = MockServer::start(vec![
+        bugs_response([1].into_iter()),
+        MockResponse::ok(r#"{"bugs": []}"#),
+    ])
+    .await;
+    let instance = BzInstance::at(server.url()).unwrap();
+
+    let assigned = instance
+        .search_by_assignee("user+bugs@example.com")
+        .await
+        .unwrap();
+    let reported = instance
+        .search_by_reporter("user+bugs@example.com")
+        .await
+        .unwrap();
+
+    assert_eq!(assigned.len(), 1);
+    assert!(reported.is_empty());
+    let requests = server.requests();
+    assert!(requests[0]
+        .path
+        .starts_with("/rest/bug?assigned_to=user%2Bbugs%40example.com&"));
+    assert!(requests[1]
+        .path
+        .starts_with("/rest/bug?creator=user%2Bbugs%40example.com&"));
+}
+
+/// Check that a summary without words to search for results in no duplicates
+/// without sending a request.
+#[tokio::test]
+async fn possible_duplicates_without_terms() {
+    let server = MockServer::start(vec![bugs_response(1..=3)]).await;
+    let instance = BzInstance::at(server.url()).unwrap();
+
+    let bugs = instance
+        .possible_duplicates("It is a ...", None)
+        .await
+        .unwrap();
+
+    assert!(bugs.is_empty());
+    assert!(server.requests().is_empty());
+}
+
+/// Check that a saved search sends the encoded name and the ID of the sharer.
+#[tokio::test]
+async fn saved_search() {
+    let server = MockServer::start(vec![bugs_response([1, 2].into_iter())]).await;
+    let instance = BzInstance::at(server.url()).unwrap();
+
+    let bugs = instance
+        .saved_search("Team queue", Some("42"))
+        .await
+        .unwrap();
+
+    assert_eq!(bugs.len(), 2);
+    assert!(server.requests()[0]
+        .path
+        .starts_with("/rest/bug?savedsearch=Team%20queue&sharer_id=42&"));
+}
+
+/// Check that a saved search doesn't get narrowed by the default scope of the instance.
+#[tokio::test]
+async fn saved_search_without_default_scope() {
+    let server = MockServer::start(vec![bugs_response([1].into_iter())]).await;
+    let instance = BzInstance::at(server.url())
+        .unwrap()
+        .default_product("Fedora".to_string())
+        .default_component("rust".to_string());
+
+    instance.saved_search("Team", None).await.unwrap();
+    instance.search("savedsearch=Team").await.unwrap();
+
+    for request in server.requests() {
+        assert!(request.path.starts_with("/rest/bug?savedsearch=Team&"));
+        assert!(!request.path.contains("product="));
+        assert!(!request.path.contains("component="));
+    }
+}
+
+/// and that the request uses the configured fields.
+#[tokio::test]
+async fn raw_bug() {
+    let server = MockServer::start(vec![MockResponse::ok(BUG_FIXTURE)]).await;
+    let instance = BzInstance::at(server.url())
+        .unwrap()
+        .authenticate(Auth::ApiKey("key".to_string()))
+        .include_extra_fields(vec!["cf_doc_type".to_string()]);
+
+    let bug = instance.bug_raw("1906883").await.unwrap();
+
+    assert_eq!(bug["id"], 1906883);
+    assert_eq!(bug["cf_doc_type"], "If docs needed, set a value");
+    let requests = server.requests();
+    assert_eq!(
+        requests[0].path,
+        "/rest/bug?id=1906883&include_fields=_default,cf_doc_type"
+    );
+    assert_eq!(requests[0].header("authorization"), Some("Bearer key"));
+}
+
+/// Check that the lenient mode skips and counts the malformed bugs,
+/// while the default mode fails the whole response.
+#[tokio::test]
+async fn lenient_deserialization() {
+    let server = MockServer::start(vec![MockResponse::ok(
+        r#"{"bugs": [
+            {"id": 1, "summary": "A valid bug"},
+            {"id": 2, "creation_time": "May 1"},
+            {"summary": "A bug without an ID"},
+            {"id": 4}
+        ], "total_matches": 4}"#,
+    )])
+    .await;
+
+    let strict = BzInstance::at(server.url()).unwrap();
+    let error = strict.search_full("product=Fedora").await.unwrap_err();
+    assert!(matches!(error, BugzillaQueryError::Deserialization { .. }));
+
+    let lenient = BzInstance::at(server.url()).unwrap().lenient(true);
+    let result = lenient.search_full("product=Fedora").await.unwrap();
+    let ids: Vec<i32> = result.bugs.iter().map(|bug| bug.id).collect();
+    assert_eq!(ids, [1, 4]);
+    assert_eq!(result.skipped, 2);
+
+    let bugs = lenient.search("product=Fedora").await.unwrap();
+    assert_eq!(bugs.len(), 2);
+}
+
+/// Check that a single attachment is requested by its ID,
+/// and that a nonexistent attachment results in `NotFound`.
+#[tokio::test]
+async fn single_attachment() {
+    let server = MockServer::start(vec![
+        MockResponse::ok(
+            r#"{"bugs": {}, "attachments": {"1758323": {
+                "id": 1758323, "bug_id": 1906883, "file_name": "log.txt", "summary": "Build log",
+                "content_type": "text/plain", "size": 16, "is_patch": false, "is_obsolete": false,
+                "is_private": false, "creator": "user@example.com",
+                "creation_time": "2022-05-01T12:30:00Z", "last_change_time": "2022-05-01T12:30:00Z",
+                "data": "SGVsbG8sIEJ1Z3ppbGxhIQ=="
+            }}}"#,
+        ),
+        MockResponse::json(
+            404,
+            r#"{"error": true, "message": "Attachment #1 does not exist.", "code": 100}"#,
+        ),
+    ])
+    .await;
+    let instance = BzInstance::at(server.url()).unwrap();
+
+    let attachment = instance.attachment("1758323").await.unwrap();
+    assert_eq!(attachment.id, 1758323);
+    assert_eq!(attachment.file_name, "log.txt");
+    assert_eq!(attachment.decoded_data().unwrap(), b"Hello, Bugzilla!");
+    assert_eq!(server.requests()[0].path, "/rest/bug/attachment/1758323");
+
+    let error = instance.attachment("1").await.unwrap_err();
+    assert!(matches!(error, BugzillaQueryError::NotFound { id } if id == "1"));
+}
+
+/// Check that a single bug that doesn't exist results in `NotFound`,
+/// while a search that matches nothing results in `NoBugs`.
+#[tokio::test]
+async fn not_found_and_no_bugs() {
+    let server = MockServer::start(vec![
+        MockResponse::json(404, ERROR_FIXTURE),
+        MockResponse::ok(r#"{"bugs": [], "faults": []}"#),
+    ])
+    .await;
+    let instance = BzInstance::at(server.url()).unwrap();
+
+    let error = instance.bug("999999999").await.unwrap_err();
+    assert!(matches!(error, BugzillaQueryError::NotFound { id } if id == "999999999"));
+
+    let error = instance.bug("999999998").await.unwrap_err();
+    assert!(matches!(error, BugzillaQueryError::NotFound { id } if id == "999999998"));
+
+    let error = instance.search("product=Nonexistent").await.unwrap_err();
+    assert!(matches!(error, BugzillaQueryError::NoBugs));
+}
+
+/// Check that the single-bug lookups report a missing bug as `NotFound`,
+/// including in the strict mode and for raw bugs and aliases.
+#[tokio::test]
+async fn single_bug_not_found() {
+    let server = MockServer::start(vec![MockResponse::ok(r#"{"bugs": [], "faults": []}"#)]).await;
+    let instance = BzInstance::at(server.url()).unwrap().strict(true);
+
+    let error = instance.bug("999999999").await.unwrap_err();
+    assert!(matches!(error, BugzillaQueryError::NotFound { id } if id == "999999999"));
+
+    let error = instance.bug_raw("999999998").await.unwrap_err();
+    assert!(matches!(error, BugzillaQueryError::NotFound { id } if id == "999999998"));
+
+    let error = instance.bug_by_alias("nonexistent").await.unwrap_err();
+    assert!(matches!(error, BugzillaQueryError::NotFound { id } if id == "nonexistent"));
+}
+
+/// Check that a single comment is requested by its ID,
+/// and that a nonexistent comment results in `NotFound`.
+#[tokio::test]
+async fn single_comment() {
+    let server = MockServer::start(vec![
+        MockResponse::ok(
+            r#"{"bugs": {}, "comments": {"14782391": {
+                "id": 14782391, "bug_id": 1906883, "attachment_id": null, "count": 3,
+                "text": "Fixed in the latest build.", "creator": "user@example.com",
+                "time": "2022-05-01T12:30:00Z", "creation_time": "2022-05-01T12:30:00Z",
+                "is_private": false, "tags": []
+            }}}"#,
+        ),
+        MockResponse::json(
+            404,
+            r#"{"error": true, "message": "There is no comment with the ID 1.", "code": 111}"#,
+        ),
+    ])
+    .await;
+    let instance = BzInstance::at(server.url()).unwrap();
+
+    let comment = instance.comment("14782391").await.unwrap();
+    assert_eq!(comment.id, 14782391);
+    assert_eq!(comment.bug_id, 1906883);
+    assert_eq!(comment.count, 3);
+    assert_eq!(server.requests()[0].path, "/rest/bug/comment/14782391");
+
+    let error = instance.comment("1").await.unwrap_err();
+    assert!(matches!(error, BugzillaQueryError::NotFound { id } if id == "1"));
+}
+
+/// Check that a response that doesn't match the bug model reports a snippet
+/// of the response around the failed field.
+#[tokio::test]
+async fn deserialization_snippet() {
+    let server = MockServer::start(vec![
+        MockResponse::ok(
+            r#"{"bugs": [{"id": 1906883, "summary": "A bug", "depends_on": "not a list", "blocks": []}]}"#,
+        ),
+        MockResponse::ok(r#"{"bugs": [{"id": 1906883, "summ"#),
+    ])
+    .await;
+    let instance = BzInstance::at(server.url()).unwrap();
+
+    let error = instance.bug("1906883").await.unwrap_err();
+    match error {
+        BugzillaQueryError::Deserialization { snippet, .. } => {
+            assert!(
+                snippet.contains(r#""depends_on": "not a list""#),
+                "{snippet}"
+            );
+        }
+        other => panic!("Unexpected error: {other:?}"),
+    }
+
+    let error = instance.bug("1906883").await.unwrap_err();
+    match error {
+        BugzillaQueryError::Deserialization { snippet, .. } => {
+            assert!(snippet.ends_with(r#""summ"#), "{snippet}");
+        }
+        other => panic!("Unexpected error: {other:?}"),
+    }
+}
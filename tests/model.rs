@@ -0,0 +1,680 @@
+use serde::Deserialize;
+use serde_json::json;
+
+use bugzilla_query::*;
+
+/// Check that a bug with only some of its fields deserializes, and that the other fields are empty.
+#[test]
+fn deserialize_partial_bug() {
+    let bug: Bug = serde_json::from_value(json!({
+        "id": 1906887,
+        "summary": "Test the CoRN release notes generator (populated)",
+    }))
+    .unwrap();
+
+    assert_eq!(bug.id, 1906887);
+    assert_eq!(
+        bug.summary.as_deref(),
+        Some("Test the CoRN release notes generator (populated)")
+    );
+    assert_eq!(bug.status, None);
+    assert_eq!(bug.creator_detail, None);
+    assert_eq!(bug.creation_time, None);
+    assert_eq!(bug.alias, OneOrMany::None);
+    assert!(bug.depends_on.is_empty());
+}
+
+/// Check that a bug without a QA contact deserializes, whether the instance
+/// sends `null` or omits the field.
+#[test]
+fn deserialize_missing_qa_contact() {
+    let bug: Bug = serde_json::from_value(json!({"id": 1, "qa_contact": null})).unwrap();
+    assert_eq!(bug.qa_contact, None);
+    assert_eq!(bug.qa_contact_detail, None);
+
+    let bug: Bug = serde_json::from_value(json!({"id": 1, "summary": "No QA"})).unwrap();
+    assert_eq!(bug.qa_contact, None);
+
+    let bug: Bug =
+        serde_json::from_value(json!({"id": 1, "qa_contact": "qa@example.com"})).unwrap();
+    assert_eq!(bug.qa_contact.as_deref(), Some("qa@example.com"));
+}
+
+/// Check that a deadline deserializes as a date, and that a missing
+/// or an empty deadline deserializes as `None`.
+#[test]
+fn deserialize_deadline() {
+    let bug: Bug = serde_json::from_value(json!({"id": 1, "deadline": "2022-06-30"})).unwrap();
+    assert_eq!(
+        bug.deadline,
+        Some(chrono::NaiveDate::from_ymd_opt(2022, 6, 30).unwrap())
+    );
+
+    for deadline in [json!(""), json!(null)] {
+        let bug: Bug = serde_json::from_value(json!({"id": 1, "deadline": deadline})).unwrap();
+        assert_eq!(bug.deadline, None);
+    }
+
+    let bug: Bug = serde_json::from_value(json!({"id": 1})).unwrap();
+    assert_eq!(bug.deadline, None);
+}
+
+/// Check that the timestamps deserialize from all formats that Bugzilla instances use,
+/// and that an empty timestamp deserializes as `None`.
+#[test]
+fn deserialize_timestamp_formats() {
+    let expected = chrono::DateTime::parse_from_rfc3339("2022-05-01T12:30:00Z")
+        .unwrap()
+        .with_timezone(&chrono::Utc);
+
+    for timestamp in [
+        "2022-05-01T12:30:00Z",
+        "2022-05-01T14:30:00+02:00",
+        "2022-05-01T12:30:00",
+        "2022-05-01 12:30:00",
+    ] {
+        let bug: Bug = serde_json::from_value(json!({
+            "id": 1,
+            "creation_time": timestamp,
+            "last_change_time": timestamp,
+        }))
+        .unwrap();
+        assert_eq!(bug.creation_time, Some(expected));
+        assert_eq!(bug.last_change_time, Some(expected));
+    }
+
+    let bug: Bug = serde_json::from_value(json!({"id": 1, "creation_time": ""})).unwrap();
+    assert_eq!(bug.creation_time, None);
+
+    let invalid = serde_json::from_value::<Bug>(json!({"id": 1, "creation_time": "May 1"}));
+    assert!(invalid.is_err());
+}
+
+/// Check that standard statuses deserialize to their own variants,
+/// and that other statuses remain available as custom ones.
+#[test]
+fn deserialize_status() {
+    let bug: Bug = serde_json::from_value(json!({"id": 1, "status": "IN_PROGRESS"})).unwrap();
+    assert_eq!(bug.status, Some(Status::InProgress));
+    assert_eq!(bug.status_raw(), Some("IN_PROGRESS"));
+
+    let bug: Bug = serde_json::from_value(json!({"id": 2, "status": "ON_QA"})).unwrap();
+    assert_eq!(bug.status, Some(Status::Custom("ON_QA".to_string())));
+    assert_eq!(bug.status_raw(), Some("ON_QA"));
+}
+
+/// Check that standard resolutions deserialize to their own variants,
+/// that open bugs have the empty resolution, and that other resolutions
+/// remain available as custom ones.
+#[test]
+fn deserialize_resolution() {
+    let bug: Bug = serde_json::from_value(json!({"id": 1, "resolution": "WONTFIX"})).unwrap();
+    assert_eq!(bug.resolution, Some(Resolution::WontFix));
+    assert_eq!(bug.resolution_raw(), Some("WONTFIX"));
+
+    let bug: Bug = serde_json::from_value(json!({"id": 2, "resolution": ""})).unwrap();
+    assert_eq!(bug.resolution, Some(Resolution::Empty));
+    assert_eq!(bug.resolution_raw(), Some(""));
+
+    let bug: Bug =
+        serde_json::from_value(json!({"id": 3, "resolution": "CURRENTRELEASE"})).unwrap();
+    assert_eq!(
+        bug.resolution,
+        Some(Resolution::Custom("CURRENTRELEASE".to_string()))
+    );
+    assert_eq!(bug.resolution_raw(), Some("CURRENTRELEASE"));
+}
+
+/// Check that custom fields deserialize into a user-defined struct.
+#[test]
+fn deserialize_custom_fields() {
+    #[derive(Deserialize)]
+    struct RhFields {
+        cf_release_notes: Option<String>,
+        cf_pm_score: Option<i64>,
+        cf_doc_type: Option<String>,
+    }
+
+    let bug: Bug = serde_json::from_value(json!({
+        "id": 1906887,
+        "cf_release_notes": "A release note.",
+        "cf_pm_score": 15,
+    }))
+    .unwrap();
+
+    let fields: RhFields = bug.custom_fields().unwrap();
+    assert_eq!(fields.cf_release_notes.as_deref(), Some("A release note."));
+    assert_eq!(fields.cf_pm_score, Some(15));
+    assert_eq!(fields.cf_doc_type, None);
+
+    // A field with an unexpected type results in an error.
+    #[derive(Debug, Deserialize)]
+    struct WrongFields {
+        #[allow(dead_code)]
+        cf_pm_score: String,
+    }
+    assert!(matches!(
+        bug.custom_fields::<WrongFields>(),
+        Err(BugzillaQueryError::CustomFields(_))
+    ));
+}
+
+/// Check that a bug serializes back to JSON with the same fields, including the extra ones.
+#[test]
+fn serialize_round_trip() {
+    let original = json!({
+        "id": 1906887,
+        "summary": "Test the CoRN release notes generator (populated)",
+        "status": "CLOSED",
+        "alias": "corn",
+        "component": ["Documentation"],
+        "flags": [{
+            "id": 4833818,
+            "type_id": 415,
+            "creation_date": "2020-12-11T14:53:57Z",
+            "modification_date": "2020-12-11T14:53:57Z",
+            "name": "requires_doc_text",
+            "status": "+",
+            "setter": "msuchane@redhat.com",
+        }],
+        "cf_release_notes": "A release note.",
+    });
+    let bug: Bug = serde_json::from_value(original).unwrap();
+
+    let serialized = serde_json::to_value(&bug).unwrap();
+    assert_eq!(serialized["id"], 1906887);
+    assert_eq!(serialized["status"], "CLOSED");
+    // `OneOrMany` keeps its original shape.
+    assert_eq!(serialized["alias"], "corn");
+    assert_eq!(serialized["component"], json!(["Documentation"]));
+    assert_eq!(serialized["flags"][0]["name"], "requires_doc_text");
+    // The extra fields appear at the top level again.
+    assert_eq!(serialized["cf_release_notes"], "A release note.");
+
+    let deserialized: Bug = serde_json::from_value(serialized).unwrap();
+    assert_eq!(deserialized, bug);
+}
+
+/// Check that a bug displays on a single line, with only the available fields.
+#[test]
+fn display_bug() {
+    let bug = Bug {
+        id: 1906887,
+        status: Some(Status::Closed),
+        summary: Some("Test the CoRN release notes generator".to_string()),
+        ..Bug::default()
+    };
+    assert_eq!(
+        bug.to_string(),
+        "#1906887 [CLOSED] Test the CoRN release notes generator"
+    );
+
+    let bug = Bug {
+        id: 1906887,
+        ..Bug::default()
+    };
+    assert_eq!(bug.to_string(), "#1906887");
+}
+
+/// A bug with the `requires_doc_text` and `qe_test_coverage` flags.
+fn bug_with_flags() -> Bug {
+    let flag = |id: i32, name: &str, status: &str| {
+        json!({
+            "id": id,
+            "type_id": 415,
+            "creation_date": "2020-12-11T14:53:57Z",
+            "modification_date": "2020-12-11T14:53:57Z",
+            "name": name,
+            "status": status,
+            "setter": "msuchane@redhat.com",
+        })
+    };
+
+    serde_json::from_value(json!({
+        "id": 1906887,
+        "flags": [
+            flag(1, "requires_doc_text", "+"),
+            flag(2, "qe_test_coverage", "-"),
+        ],
+    }))
+    .unwrap()
+}
+
+/// Check flag lookup when the flag is present, when it's absent, and when flags aren't loaded.
+#[test]
+fn try_get_flag() {
+    let bug = bug_with_flags();
+    assert!(bug.flags_loaded());
+    assert_eq!(bug.try_get_flag("requires_doc_text"), Some("+"));
+    assert_eq!(bug.try_get_flag("needinfo"), None);
+
+    let bug = Bug { flags: None, ..bug };
+    assert!(!bug.flags_loaded());
+    assert_eq!(bug.try_get_flag("requires_doc_text"), None);
+}
+
+/// Check that the flags map contains all flags, and that it's empty without flags.
+#[test]
+fn flags_map() {
+    let bug = bug_with_flags();
+    let flags = bug.flags_map();
+
+    assert_eq!(flags.len(), 2);
+    assert_eq!(flags.get("requires_doc_text"), Some(&"+"));
+    assert_eq!(flags.get("qe_test_coverage"), Some(&"-"));
+
+    // Without loaded flags, the map is empty and `get_flag` doesn't panic.
+    let bug = Bug { flags: None, ..bug };
+    assert!(bug.flags_map().is_empty());
+    assert_eq!(bug.get_flag("requires_doc_text"), None);
+}
+
+/// Check that the flags list the loaded flags, and that they're empty without flags.
+#[test]
+fn flags_slice() {
+    let bug = bug_with_flags();
+    let names: Vec<&str> = bug.flags().iter().map(|flag| flag.name.as_str()).collect();
+    assert_eq!(names, ["requires_doc_text", "qe_test_coverage"]);
+
+    let bug = Bug { flags: None, ..bug };
+    assert!(bug.flags().is_empty());
+}
+
+/// Check the status predicates over flags in each state.
+#[test]
+fn flag_status() {
+    let flag = |status: &str, requestee: Option<&str>| -> Flag {
+        serde_json::from_value(json!({
+            "id": 1,
+            "type_id": 415,
+            "creation_date": "2020-12-11T14:53:57Z",
+            "modification_date": "2020-12-11T14:53:57Z",
+            "name": "needinfo",
+            "status": status,
+            "setter": "msuchane@redhat.com",
+            "requestee": requestee,
+        }))
+        .unwrap()
+    };
+
+    let granted = flag("+", None);
+    assert!(granted.is_granted());
+    assert!(!granted.is_denied());
+    assert!(!granted.is_requested());
+
+    let denied = flag("-", None);
+    assert!(!denied.is_granted());
+    assert!(denied.is_denied());
+    assert!(!denied.is_requested());
+
+    let requested = flag("?", Some("someone@redhat.com"));
+    assert!(!requested.is_granted());
+    assert!(!requested.is_denied());
+    assert!(requested.is_requested());
+    assert_eq!(requested.requestee.as_deref(), Some("someone@redhat.com"));
+}
+
+/// Check that the attachment data decodes from base64 to the original content.
+#[test]
+fn decode_attachment_data() {
+    let attachment: Attachment = serde_json::from_value(json!({
+        "id": 1755191,
+        "bug_id": 1906887,
+        "file_name": "hello.txt",
+        "summary": "A greeting",
+        "content_type": "text/plain",
+        "size": 17,
+        "is_patch": false,
+        "is_obsolete": false,
+        "is_private": false,
+        "creator": "msuchane@redhat.com",
+        "creation_time": "2020-12-11T14:53:57Z",
+        "last_change_time": "2020-12-11T14:53:57Z",
+        "data": "SGVsbG8sIEJ1Z3ppbGxhIQ==",
+    }))
+    .unwrap();
+
+    assert_eq!(attachment.decoded_data().unwrap(), b"Hello, Bugzilla!");
+
+    // Without the data, decoding reports the missing data instead of returning nothing.
+    let attachment = Attachment {
+        data: None,
+        ..attachment
+    };
+    assert!(matches!(
+        attachment.decoded_data(),
+        Err(BugzillaQueryError::MissingAttachmentData)
+    ));
+}
+
+/// Check the time-tracking fields in hours, including the fields that are missing.
+#[test]
+fn time_tracking() {
+    let bug = Bug {
+        estimated_time: Some(8.into()),
+        remaining_time: Some(3.into()),
+        actual_time: Some(5.into()),
+        ..Bug::default()
+    };
+
+    assert_eq!(bug.estimated_time_hours(), Some(8.0));
+    assert_eq!(
+        bug.time_tracking(),
+        TimeTracking {
+            estimated_time: Some(8.0),
+            remaining_time: Some(3.0),
+            actual_time: Some(5.0),
+            work_time: None,
+        }
+    );
+    assert_eq!(Bug::default().time_tracking(), TimeTracking::default());
+}
+
+/// Check that the time-tracking fields deserialize from decimal numbers of hours.
+#[test]
+fn fractional_time_tracking() {
+    let bug: Bug = serde_json::from_value(json!({
+        "id": 1906887,
+        "estimated_time": 2.5,
+        "remaining_time": 0.75,
+        "actual_time": 1,
+        "work_time": 0.0,
+    }))
+    .unwrap();
+
+    assert_eq!(
+        bug.time_tracking(),
+        TimeTracking {
+            estimated_time: Some(2.5),
+            remaining_time: Some(0.75),
+            actual_time: Some(1.0),
+            work_time: Some(0.0),
+        }
+    );
+}
+
+/// Check that each kind of link in `see_also` is classified correctly.
+#[test]
+fn see_also_links() {
+    let links = [
+        "https://bugzilla.redhat.com/show_bug.cgi?id=1906883",
+        "https://bugzilla.mozilla.org/bugzilla/show_bug.cgi?format=multiple&id=1",
+        "https://github.com/msuchane/bugzilla_query/issues/1",
+        "https://issues.redhat.com/browse/RHEL-1234",
+        "https://example.com/jira/browse/DOC_2-56",
+        "https://bugzilla.redhat.com/show_bug.cgi?id=invalid",
+        "https://example.com/browse/lowercase-1",
+        "not a URL",
+    ];
+    let bug = Bug {
+        see_also: Some(links.iter().map(|link| link.to_string()).collect()),
+        ..Bug::default()
+    };
+
+    assert_eq!(
+        bug.see_also_links(),
+        vec![
+            SeeAlsoLink::Bugzilla {
+                host: "bugzilla.redhat.com".to_string(),
+                id: 1906883
+            },
+            SeeAlsoLink::Bugzilla {
+                host: "bugzilla.mozilla.org".to_string(),
+                id: 1
+            },
+            SeeAlsoLink::GitHub {
+                url: links[2].to_string()
+            },
+            SeeAlsoLink::Jira {
+                url: links[3].to_string()
+            },
+            SeeAlsoLink::Jira {
+                url: links[4].to_string()
+            },
+            SeeAlsoLink::Other(links[5].to_string()),
+            SeeAlsoLink::Other(links[6].to_string()),
+            SeeAlsoLink::Other(links[7].to_string()),
+        ]
+    );
+    assert!(Bug::default().see_also_links().is_empty());
+}
+
+/// Check that the watermark is the latest change time, skipping bugs without the field.
+#[test]
+fn latest_change_time_watermark() {
+    let bug = |id, time: Option<&str>| Bug {
+        id,
+        last_change_time: time.map(|time| {
+            chrono::DateTime::parse_from_rfc3339(time)
+                .unwrap()
+                .with_timezone(&chrono::Utc)
+        }),
+        ..Bug::default()
+    };
+    let bugs = [
+        bug(1, Some("2022-05-01T12:30:00Z")),
+        bug(2, Some("2022-06-15T08:00:00Z")),
+        bug(3, None),
+        bug(4, Some("2022-06-01T23:59:59Z")),
+    ];
+
+    assert_eq!(latest_change_time(&bugs), bugs[1].last_change_time);
+    assert_eq!(latest_change_time(&bugs[2..3]), None);
+    assert_eq!(latest_change_time(&[]), None);
+}
+
+/// Check that comment tags deserialize, and that they default to none
+/// on instances that disable comment tagging.
+#[test]
+fn comment_tags() {
+    let comment = |tags: Option<Value>| {
+        let mut comment = json!({
+            "id": 1,
+            "bug_id": 1906883,
+            "attachment_id": null,
+            "count": 1,
+            "text": "Buy cheap watches",
+            "creator": "spammer@example.com",
+            "time": "2022-05-01T12:30:00Z",
+            "creation_time": "2022-05-01T12:30:00Z",
+            "is_private": false,
+        });
+        if let Some(tags) = tags {
+            comment["tags"] = tags;
+        }
+        serde_json::from_value::<Comment>(comment).unwrap()
+    };
+
+    let tagged = comment(Some(json!(["spam", "obsolete"])));
+    assert_eq!(tagged.tags, ["spam", "obsolete"]);
+    assert!(tagged.has_tag("spam"));
+    assert!(tagged.has_tag("SPAM"));
+    assert!(!tagged.has_tag("duplicate"));
+
+    let untagged = comment(None);
+    assert!(untagged.tags.is_empty());
+    assert!(!untagged.has_tag("spam"));
+}
+
+/// Check the status predicates on bugs in various states, including lowercase values.
+#[test]
+fn status_predicates() {
+    let bug = |status: &str, resolution: &str| Bug {
+        id: 1,
+        status: Some(Status::from(status.to_string())),
+        resolution: Some(Resolution::from(resolution.to_string())),
+        ..Bug::default()
+    };
+
+    let open = bug("NEW", "");
+    assert!(!open.is_duplicate());
+    assert!(!open.is_resolved());
+    assert!(!open.is_verified());
+
+    let duplicate = bug("CLOSED", "DUPLICATE");
+    assert!(duplicate.is_duplicate());
+    assert!(duplicate.is_resolved());
+    assert!(!duplicate.is_verified());
+
+    let verified = bug("verified", "fixed");
+    assert!(!verified.is_duplicate());
+    assert!(verified.is_resolved());
+    assert!(verified.is_verified());
+
+    let lowercase_duplicate = bug("closed", "duplicate");
+    assert!(lowercase_duplicate.is_duplicate());
+
+    let partial = Bug::default();
+    assert!(!partial.is_duplicate());
+    assert!(!partial.is_resolved());
+    assert!(!partial.is_verified());
+}
+
+/// Check the group predicates on public and restricted bugs.
+#[test]
+fn group_predicates() {
+    let public = Bug::default();
+    assert!(!public.is_restricted());
+    assert!(!public.in_group("redhat"));
+
+    let restricted = Bug {
+        groups: vec!["redhat".to_string(), "security".to_string()],
+        ..Bug::default()
+    };
+    assert!(restricted.is_restricted());
+    assert!(restricted.in_group("security"));
+    assert!(!restricted.in_group("qe_staff"));
+}
+
+/// Check that the whiteboard parses into tags, skipping stray text and broken brackets.
+#[test]
+fn whiteboard_tags() {
+    let bug = Bug {
+        whiteboard: Some(
+            "needs triage [priority:high][sprint: 42] [blocker] see comment 3 [] [url:https://example.com] [unclosed"
+                .to_string(),
+        ),
+        ..Bug::default()
+    };
+
+    assert_eq!(
+        bug.whiteboard_tags(),
+        vec![
+            ("priority".to_string(), Some("high".to_string())),
+            ("sprint".to_string(), Some("42".to_string())),
+            ("blocker".to_string(), None),
+            ("url".to_string(), Some("https://example.com".to_string())),
+        ]
+    );
+
+    assert!(Bug::default().whiteboard_tags().is_empty());
+}
+
+/// Check that the changes in a time window include the start and exclude the end.
+#[test]
+fn changes_in_window() {
+    let time = |time: &str| {
+        chrono::DateTime::parse_from_rfc3339(time)
+            .unwrap()
+            .with_timezone(&chrono::Utc)
+    };
+    let entry = |when: &str, fields: &[&str]| HistoryEntry {
+        when: time(when),
+        who: "user@example.com".to_string(),
+        changes: fields
+            .iter()
+            .map(|field| FieldChange {
+                field_name: field.to_string(),
+                removed: String::new(),
+                added: "value".to_string(),
+                attachment_id: None,
+            })
+            .collect(),
+    };
+    let history = [
+        entry("2022-04-30T23:59:59Z", &["priority"]),
+        entry("2022-05-01T00:00:00Z", &["status", "resolution"]),
+        entry("2022-05-04T12:00:00Z", &["cc"]),
+        entry("2022-05-08T00:00:00Z", &["summary"]),
+    ];
+
+    let changes = changes_between(
+        &history,
+        time("2022-05-01T00:00:00Z"),
+        time("2022-05-08T00:00:00Z"),
+    );
+
+    let fields: Vec<&str> = changes
+        .iter()
+        .map(|change| change.field_name.as_str())
+        .collect();
+    assert_eq!(fields, ["status", "resolution", "cc"]);
+}
+
+/// Check that a result is truncated when more bugs match than the pages up to this one contain.
+#[test]
+fn truncated_search_result() {
+    let result = |bugs: usize, offset: Option<u32>, total_matches: Option<u32>| SearchResult {
+        bugs: vec![Bug::default(); bugs],
+        offset,
+        limit: None,
+        total_matches,
+        skipped: 0,
+    };
+
+    assert!(result(20, Some(0), Some(35)).is_truncated());
+    assert!(!result(20, Some(0), Some(20)).is_truncated());
+    // The last page isn't truncated.
+    assert!(!result(15, Some(20), Some(35)).is_truncated());
+    assert!(!result(20, None, None).is_truncated());
+}
+
+/// Check the helpers of `OneOrMany` on each variant.
+#[test]
+fn one_or_many_helpers() {
+    let none = OneOrMany::None;
+    let one = OneOrMany::One("rust".to_string());
+    let many = OneOrMany::Many(vec!["rust".to_string(), "cargo".to_string()]);
+    let empty_many = OneOrMany::Many(Vec::new());
+
+    assert_eq!(none.first(), None);
+    assert_eq!(one.first(), Some("rust"));
+    assert_eq!(many.first(), Some("rust"));
+    assert_eq!(empty_many.first(), None);
+
+    assert!(none.is_empty());
+    assert!(!one.is_empty());
+    assert!(!many.is_empty());
+    assert!(empty_many.is_empty());
+
+    assert_eq!(OneOrMany::from(Vec::new()), OneOrMany::None);
+    assert_eq!(
+        OneOrMany::from(vec!["rust".to_string(), "cargo".to_string()]),
+        many
+    );
+
+    let collected: OneOrMany = ["rust", "cargo"].iter().map(ToString::to_string).collect();
+    assert_eq!(collected, many);
+    let collected: OneOrMany = std::iter::empty().collect();
+    assert_eq!(collected, OneOrMany::None);
+}
+
+/// Check that iterating over `OneOrMany` yields the items of each variant.
+#[test]
+fn iterate_one_or_many() {
+    let none = OneOrMany::None;
+    let one = OneOrMany::One("rust".to_string());
+    let many = OneOrMany::Many(vec!["rust".to_string(), "cargo".to_string()]);
+
+    assert_eq!((&none).into_iter().count(), 0);
+    assert_eq!(one.iter().collect::<Vec<_>>(), ["rust"]);
+    let mut borrowed = Vec::new();
+    for item in &many {
+        borrowed.push(item);
+    }
+    assert_eq!(borrowed, ["rust", "cargo"]);
+
+    assert_eq!(none.into_iter().count(), 0);
+    assert_eq!(one.into_iter().collect::<Vec<String>>(), ["rust"]);
+    assert_eq!(many.into_iter().collect::<Vec<String>>(), ["rust", "cargo"]);
+}
@@ -0,0 +1,43 @@
+//! Tests of the proxy settings. The system proxies come from environment variables
+//! that apply to the whole process, so these tests run in their own test binary.
+
+mod common;
+
+use bugzilla_query::*;
+use common::{MockResponse, MockServer};
+
+const SINGLE_BUG: &str = r#"{"bugs": [{"id": 1906887}]}"#;
+
+/// Check that disabling the proxies bypasses a system proxy that nothing listens on,
+/// while the instance still reaches the server.
+#[tokio::test]
+async fn no_proxy_bypasses_system_proxy() {
+    // Find a local port that nothing listens on.
+    let dead_port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let dead_proxy = format!("http://127.0.0.1:{dead_port}");
+    // Set the variables before building any client, because `reqwest` reads them only once.
+    for name in ["HTTP_PROXY", "http_proxy"] {
+        std::env::set_var(name, &dead_proxy);
+    }
+    for name in ["NO_PROXY", "no_proxy"] {
+        std::env::remove_var(name);
+    }
+
+    let server = MockServer::start(vec![MockResponse::ok(SINGLE_BUG)]).await;
+
+    // The system proxy applies by default, so the request fails.
+    let instance = BzInstance::at(server.url()).unwrap();
+    assert!(matches!(
+        instance.bug("1906887").await,
+        Err(BugzillaQueryError::Request(_))
+    ));
+
+    let instance = BzInstance::at(server.url()).unwrap().no_proxy();
+    let bug = instance.bug("1906887").await.unwrap();
+    assert_eq!(bug.id, 1906887);
+    assert_eq!(server.requests().len(), 1);
+}
@@ -0,0 +1,140 @@
+//! Tests of building search queries, which don't send any requests.
+
+use bugzilla_query::*;
+use chrono::{DateTime, Utc};
+
+/// Parse a timestamp in the RFC 3339 format.
+fn time(timestamp: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .unwrap()
+        .with_timezone(&Utc)
+}
+
+/// Check that advanced search conditions are numbered in order, and that the values
+/// are URL-encoded.
+#[test]
+fn advanced_search_query() {
+    let search = SearchBuilder::new()
+        .param("product", "Red Hat Enterprise Linux 9")
+        .field("component")
+        .op(Op::Equals)
+        .value("C/C++ tools")
+        .and()
+        .field("short_desc")
+        .op(Op::Substring)
+        .value("crash & burn")
+        .and()
+        .field("bug_status")
+        .op(Op::NotEquals)
+        .value("CLOSED");
+
+    assert_eq!(
+        search.to_query(),
+        "product=Red%20Hat%20Enterprise%20Linux%209\
+        &f1=component&o1=equals&v1=C%2FC%2B%2B%20tools\
+        &f2=short_desc&o2=substring&v2=crash%20%26%20burn\
+        &f3=bug_status&o3=notequals&v3=CLOSED"
+    );
+}
+
+/// Check that a duplicate search matches any of the words in the summary,
+/// without the punctuation, in the specified product.
+#[test]
+fn possible_duplicates_query() {
+    let search =
+        SearchBuilder::possible_duplicates("Crash on startup: \"C++\" & ACLs.", Some("Fedora"))
+            .unwrap();
+
+    assert_eq!(
+        search.to_query(),
+        "product=Fedora&f1=short_desc&o1=anywords&v1=Crash%20startup%20ACLs"
+    );
+
+    let search = SearchBuilder::possible_duplicates("Crash", None).unwrap();
+    assert_eq!(search.to_query(), "f1=short_desc&o1=anywords&v1=Crash");
+}
+
+/// Check that a duplicate search skips the common and short words,
+/// and that a summary without any other words results in no search.
+#[test]
+fn possible_duplicates_stop_words() {
+    let search =
+        SearchBuilder::possible_duplicates("The window is not shown when I log in", None).unwrap();
+    assert_eq!(
+        search.to_query(),
+        "f1=short_desc&o1=anywords&v1=window%20shown%20log"
+    );
+
+    assert!(SearchBuilder::possible_duplicates("", None).is_none());
+    assert!(SearchBuilder::possible_duplicates("?! -- ...", Some("Fedora")).is_none());
+    assert!(SearchBuilder::possible_duplicates("It is not in the", None).is_none());
+}
+
+/// Check that each bound of a time range adds a condition, and that a missing bound
+/// adds no condition.
+#[test]
+fn time_range_queries() {
+    let start = time("2022-01-01T00:00:00Z");
+    let end = time("2022-03-31T23:59:59Z");
+
+    let search = SearchBuilder::new().created_between(Some(start), Some(end));
+    assert_eq!(
+        search.to_query(),
+        "f1=creation_ts&o1=greaterthan&v1=2022-01-01T00%3A00%3A00Z\
+        &f2=creation_ts&o2=lessthan&v2=2022-03-31T23%3A59%3A59Z"
+    );
+
+    let search = SearchBuilder::new()
+        .param("product", "Fedora")
+        .created_between(None, Some(end))
+        .changed_between(Some(start), None);
+    assert_eq!(
+        search.to_query(),
+        "product=Fedora\
+        &f1=creation_ts&o1=lessthan&v1=2022-03-31T23%3A59%3A59Z\
+        &f2=delta_ts&o2=greaterthan&v2=2022-01-01T00%3A00%3A00Z"
+    );
+
+    let search = SearchBuilder::new()
+        .created_between(None, None)
+        .changed_between(None, None);
+    assert_eq!(search.to_query(), "");
+}
+
+/// Check that the keywords are URL-encoded, and that the match mode selects the operator.
+#[test]
+fn keywords_query() {
+    let keywords = ["Triaged", "Doc&Release"];
+
+    let search = SearchBuilder::new().keywords(&keywords, true);
+    assert_eq!(
+        search.to_query(),
+        "keywords=Triaged%20Doc%26Release&keywords_type=allwords"
+    );
+
+    let search = SearchBuilder::new().keywords(&keywords, false);
+    assert_eq!(
+        search.to_query(),
+        "keywords=Triaged%20Doc%26Release&keywords_type=anywords"
+    );
+}
+
+/// Check that the searches by user use the right fields and encode the email.
+#[test]
+fn user_queries() {
+    let search = SearchBuilder::new().assigned_to("user+bugs@example.com");
+    assert_eq!(search.to_query(), "assigned_to=user%2Bbugs%40example.com");
+
+    let search = SearchBuilder::new().reporter("user+bugs@example.com");
+    assert_eq!(search.to_query(), "creator=user%2Bbugs%40example.com");
+}
+
+/// Check that a saved search encodes its name and only includes the sharer if specified.
+#[test]
+fn saved_search_query() {
+    let search = SearchBuilder::saved_search("My bugs & more", None);
+    assert_eq!(search.to_query(), "savedsearch=My%20bugs%20%26%20more");
+
+    let search = SearchBuilder::saved_search("Team queue", Some("42"));
+    assert_eq!(search.to_query(), "savedsearch=Team%20queue&sharer_id=42");
+}
@@ -0,0 +1,116 @@
+//! Tests of the `BugSource` trait with an in-memory implementation and with `BzInstance`.
+
+mod common;
+
+use std::collections::HashMap;
+
+use bugzilla_query::*;
+use common::{MockResponse, MockServer};
+use futures_util::future::BoxFuture;
+
+/// A fake source of bugs that keeps the bugs in memory and searches by product.
+struct InMemory {
+    bugs: HashMap<String, Bug>,
+}
+
+impl InMemory {
+    fn new(bugs: Vec<Bug>) -> Self {
+        let bugs = bugs
+            .into_iter()
+            .map(|bug| (bug.id.to_string(), bug))
+            .collect();
+        Self { bugs }
+    }
+}
+
+impl BugSource for InMemory {
+    fn bug<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<Bug, BugzillaQueryError>> {
+        Box::pin(async move {
+            self.bugs
+                .get(id)
+                .cloned()
+                .ok_or_else(|| BugzillaQueryError::NotFound { id: id.to_string() })
+        })
+    }
+
+    fn bugs<'a>(
+        &'a self,
+        ids: &'a [&'a str],
+    ) -> BoxFuture<'a, Result<Vec<Bug>, BugzillaQueryError>> {
+        Box::pin(async move {
+            Ok(ids
+                .iter()
+                .filter_map(|id| self.bugs.get(*id).cloned())
+                .collect())
+        })
+    }
+
+    fn search<'a>(&'a self, query: &'a str) -> BoxFuture<'a, Result<Vec<Bug>, BugzillaQueryError>> {
+        Box::pin(async move {
+            let product = query.strip_prefix("product=");
+            let mut bugs: Vec<Bug> = self
+                .bugs
+                .values()
+                .filter(|bug| bug.product.as_deref() == product)
+                .cloned()
+                .collect();
+            bugs.sort_by_key(|bug| bug.id);
+            Ok(bugs)
+        })
+    }
+}
+
+/// Code that depends on the crate and accepts any source of bugs.
+async fn product_summaries(source: &impl BugSource, product: &str) -> Vec<String> {
+    let query = format!("product={product}");
+    let bugs = source.search(&query).await.unwrap();
+    bugs.into_iter().filter_map(|bug| bug.summary).collect()
+}
+
+fn bug(id: i32, product: &str, summary: &str) -> Bug {
+    Bug {
+        id,
+        product: Some(product.to_string()),
+        summary: Some(summary.to_string()),
+        ..Bug::default()
+    }
+}
+
+/// Check that an in-memory implementation replaces `BzInstance`.
+#[tokio::test]
+async fn in_memory_source() {
+    let source = InMemory::new(vec![
+        bug(1, "Fedora", "First bug"),
+        bug(2, "RHEL", "Second bug"),
+        bug(3, "Fedora", "Third bug"),
+    ]);
+
+    assert_eq!(
+        product_summaries(&source, "Fedora").await,
+        ["First bug", "Third bug"]
+    );
+    assert_eq!(BugSource::bug(&source, "2").await.unwrap().id, 2);
+    assert_eq!(
+        BugSource::bugs(&source, &["1", "4"]).await.unwrap().len(),
+        1
+    );
+    assert!(matches!(
+        BugSource::bug(&source, "4").await,
+        Err(BugzillaQueryError::NotFound { .. })
+    ));
+}
+
+/// Check that `BzInstance` works through the trait.
+#[tokio::test]
+async fn instance_as_source() {
+    let server = MockServer::start(vec![MockResponse::ok(
+        r#"{"bugs": [{"id": 1, "summary": "First bug"}]}"#,
+    )])
+    .await;
+    let instance = BzInstance::at(server.url()).unwrap();
+
+    assert_eq!(product_summaries(&instance, "Fedora").await, ["First bug"]);
+    assert!(server.requests()[0]
+        .path
+        .starts_with("/rest/bug?product=Fedora&"));
+}